@@ -0,0 +1,74 @@
+// compares the bitboard win check against the original HashMap based one.
+// run with `cargo bench`
+#[path = "../tests/common/mod.rs"]
+mod common;
+use common::random_record;
+use quarto::*;
+use std::{
+    collections::HashMap,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const BOARDS: usize = 1_000;
+const ROUNDS: usize = 100;
+
+// the win check from before the bitboard representation
+fn hash_map_has_win(b: &Board) -> bool {
+    fn line_has_win(line: &[Option<Piece>; 4]) -> bool {
+        match (line[0], line[1], line[2], line[3]) {
+            (Some(a), Some(b), Some(c), Some(d)) => {
+                let mut m = HashMap::new();
+                for p in [a, b, c, d].iter() {
+                    m.entry(Attribute::C(p.color)).and_modify(|v| *v += 1).or_insert(1);
+                    m.entry(Attribute::H(p.height)).and_modify(|v| *v += 1).or_insert(1);
+                    m.entry(Attribute::S(p.shape)).and_modify(|v| *v += 1).or_insert(1);
+                    m.entry(Attribute::T(p.top)).and_modify(|v| *v += 1).or_insert(1);
+                }
+                m.iter().any(|(_, &x)| x == 4)
+            },
+            _ => false,
+        }
+    }
+
+    ALL_LINES.iter().any(|&line| line_has_win(&b.line(line)))
+}
+
+// boards from random games, taken at every ply
+fn random_boards() -> Vec<Game> {
+    let mut games = vec![];
+    let mut seed = 0;
+    while games.len() < BOARDS {
+        let mut game = quarto::new_game().to_game();
+        for &m in random_record(seed, 32).moves() {
+            game = game.apply(m).unwrap();
+            games.push(game);
+        }
+        seed += 1;
+    }
+    games
+}
+
+fn time<F: Fn(&Game) -> bool>(games: &[Game], f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for g in games {
+            black_box(f(black_box(g)));
+        }
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let games = random_boards();
+    assert!(games.iter().all(|g| g.has_win() == hash_map_has_win(g.board())));
+
+    let checks = (games.len() * ROUNDS) as u32;
+    let hash_map = time(&games, |g| hash_map_has_win(g.board()));
+    let bitboard = time(&games, |g| g.has_win());
+
+    println!("has_win over {} boards", checks);
+    println!("  hash map: {:>10?} ({:?} per check)", hash_map, hash_map / checks);
+    println!("  bitboard: {:>10?} ({:?} per check)", bitboard, bitboard / checks);
+    println!("  speedup:  {:.1}x", hash_map.as_secs_f64() / bitboard.as_secs_f64());
+}
@@ -0,0 +1,91 @@
+use crate::{FinalGame, Game, Move, PassGame, PlaceGame, Final, Pass, Place, P2};
+use crate::board::Idx;
+use crate::error::MoveError;
+use crate::piece::Piece;
+use rand::{rngs::StdRng, seq::IteratorRandom, SeedableRng};
+
+/// Chooses the moves for one side of a game.
+pub trait Agent {
+    fn choose_pass(&mut self, game: &PassGame) -> Piece;
+
+    fn choose_place(&mut self, game: &PlaceGame) -> (Idx, Idx);
+
+    /// None = the game is over
+    fn choose_move(&mut self, game: &Game) -> Option<Move> {
+        match game {
+            Pass(g) => Some(Move::Pass(self.choose_pass(g))),
+            Place(g) => {
+                let (h, v) = self.choose_place(g);
+                Some(Move::Place(h, v))
+            },
+            Final(_) => None,
+        }
+    }
+}
+
+/// Plays uniformly random legal moves. The same seed always plays the same moves.
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct RandomAgent {
+    rng: StdRng,
+}
+
+impl RandomAgent {
+    pub fn new(seed: u64) -> RandomAgent {
+        RandomAgent { rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+impl Agent for RandomAgent {
+    fn choose_pass(&mut self, game: &PassGame) -> Piece {
+        // the board is never full while a piece is left to pass
+        game.legal_passes().choose(&mut self.rng).unwrap()
+    }
+
+    fn choose_place(&mut self, game: &PlaceGame) -> (Idx, Idx) {
+        // there is always an empty square for the piece in hand
+        game.legal_places().choose(&mut self.rng).unwrap()
+    }
+}
+
+// unwraps the pass an engine chose for a pass game. anything else is a bug in the engine,
+// so it only falls back to a legal pass outside of debug builds.
+pub(crate) fn chosen_pass(game: &PassGame, chosen: Option<Move>) -> Piece {
+    match chosen {
+        Some(Move::Pass(p)) => p,
+        other => {
+            debug_assert!(false, "expected a pass for {}, found {:?}", game.to_game(), other);
+            // a pass game always has a piece to pass
+            game.legal_passes().next().unwrap()
+        },
+    }
+}
+
+// unwraps the place an engine chose for a place game, the same way as `chosen_pass`
+pub(crate) fn chosen_place(game: &PlaceGame, chosen: Option<Move>) -> (Idx, Idx) {
+    match chosen {
+        Some(Move::Place(h, v)) => (h, v),
+        other => {
+            debug_assert!(false, "expected a place for {}, found {:?}", game.to_game(), other);
+            // there is always an empty square for the piece in hand
+            game.legal_places().next().unwrap()
+        },
+    }
+}
+
+/// Plays a game to the end with `p1` choosing every move for P1 and `p2` every move for P2.
+/// Stops at the first illegal move an agent chooses.
+pub fn play_out(game: Game, p1: &mut dyn Agent, p2: &mut dyn Agent) -> Result<FinalGame, MoveError> {
+    let mut game = game;
+    loop {
+        if let Final(g) = game {
+            return Ok(g);
+        }
+        let agent: &mut dyn Agent = match game.player_to_move() {
+            Some(P2) => &mut *p2,
+            _ => &mut *p1,
+        };
+        // only a final game has no move to choose
+        game = game.apply(agent.choose_move(&game).unwrap())?;
+    }
+}
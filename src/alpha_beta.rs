@@ -0,0 +1,239 @@
+use crate::{Game, Move, PassGame, PlaceGame, Player, Final, Pass, Place};
+use crate::agent::{chosen_pass, chosen_place, Agent};
+use crate::board::Idx;
+use crate::piece::Piece;
+use crate::transposition::{Bound, Entry, Replace, TranspositionTable};
+
+// a win in n plies scores WIN - n, so quicker wins score higher
+const WIN: i32 = 1000;
+// no game lasts longer than this many plies
+const MAX_PLIES: i32 = 32;
+// entries in the transposition table of each search
+const TABLE_SLOTS: usize = 1 << 16;
+
+/// The value of a position for the player to move.
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum Score {
+    /// the player to move wins within this many plies
+    Win(usize),
+    /// the player to move loses within this many plies
+    Loss(usize),
+    /// neither player can force a win within the plies searched
+    Draw,
+}
+
+/// The result of a search from one position.
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct Analysis {
+    pub best: Move,
+    pub score: Score,
+    /// the deepest search that finished, in plies
+    pub depth: usize,
+    pub nodes: u64,
+}
+
+/// An alpha-beta search where each pass and each place is its own ply.
+/// It deepens one ply at a time up to `depth`, and if `node_limit` runs out
+/// part way through a depth it keeps the move from the last depth it finished.
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct AlphaBeta {
+    pub depth: usize,
+    /// None = no limit
+    pub node_limit: Option<u64>,
+}
+
+impl Default for AlphaBeta {
+    fn default() -> AlphaBeta {
+        AlphaBeta { depth: 4, node_limit: None }
+    }
+}
+
+impl AlphaBeta {
+    /// None = the game is over
+    pub fn search(&self, game: &Game) -> Option<Analysis> {
+        let player = game.player_to_move()?;
+        let mut search = Search {
+            node_limit: self.node_limit,
+            nodes: 0,
+            aborted: false,
+            table: TranspositionTable::new(TABLE_SLOTS, Replace::DepthPreferred),
+        };
+        let mut moves = ordered_moves(game);
+        let mut analysis = None;
+
+        for depth in 1..=self.depth.max(1) {
+            let mut alpha = -WIN;
+            let mut best = None;
+            for &m in &moves {
+                // the game is not over, so every ordered move is legal
+                let child = game.apply(m).unwrap();
+                let v = search.child_value(player, child, depth - 1, 1, alpha, WIN);
+                if best.is_none() || v > alpha {
+                    alpha = v;
+                    best = Some(m);
+                }
+            }
+
+            // moves from the first depth are kept even when the limit runs out during it
+            if search.aborted && analysis.is_some() {
+                break;
+            }
+            if let Some(m) = best {
+                analysis = Some(Analysis { best: m, score: score_from(alpha), depth, nodes: search.nodes });
+                // try the best move first at the next depth
+                moves.retain(|&x| x != m);
+                moves.insert(0, m);
+            }
+            if matches!(score_from(alpha), Score::Win(_) | Score::Loss(_)) || search.aborted {
+                break;
+            }
+        }
+
+        analysis.map(|a| Analysis { nodes: search.nodes, ..a })
+    }
+}
+
+impl Agent for AlphaBeta {
+    fn choose_pass(&mut self, game: &PassGame) -> Piece {
+        chosen_pass(game, self.search(&game.to_game()).map(|a| a.best))
+    }
+
+    fn choose_place(&mut self, game: &PlaceGame) -> (Idx, Idx) {
+        chosen_place(game, self.search(&game.to_game()).map(|a| a.best))
+    }
+}
+
+struct Search {
+    node_limit: Option<u64>,
+    nodes: u64,
+    aborted: bool,
+    // scores counted from the position rather than the root, with the best move found
+    table: TranspositionTable<(i32, Option<Move>)>,
+}
+
+impl Search {
+    // the value of an unfinished game for the player to move, `ply` plies from the root
+    fn value(&mut self, game: Game, depth: usize, ply: i32, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.node_limit.is_some_and(|n| self.nodes > n) {
+            self.aborted = true;
+        }
+        let player = match game.player_to_move() {
+            Some(p) if depth > 0 && !self.aborted => p,
+            _ => return 0,
+        };
+
+        let key = game.zobrist();
+        let mut moves = ordered_moves(&game);
+        if let Some(entry) = self.table.get(key) {
+            let (v, m) = (from_table(entry.value.0, ply), entry.value.1);
+            if entry.depth >= depth {
+                match entry.bound {
+                    Bound::Exact => return v,
+                    Bound::Lower if v >= beta => return v,
+                    Bound::Upper if v <= alpha => return v,
+                    _ => {},
+                }
+            }
+            // try the best move from an earlier search first
+            if let Some(m) = m {
+                moves.retain(|&x| x != m);
+                moves.insert(0, m);
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best = (-WIN, None);
+        for m in moves {
+            let child = game.apply(m).unwrap();
+            let v = self.child_value(player, child, depth - 1, ply + 1, alpha, beta);
+            if best.1.is_none() || v > best.0 {
+                best = (v, Some(m));
+            }
+            alpha = alpha.max(v);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        // an unfinished search only knows part of the value
+        if !self.aborted {
+            let bound = if best.0 <= original_alpha {
+                Bound::Upper
+            } else if best.0 >= beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+            self.table.insert(Entry { key, depth, bound, value: (to_table(best.0, ply), best.1) });
+        }
+        best.0
+    }
+
+    // the value of the game after `player` moved, for `player`
+    fn child_value(&mut self, player: Player, child: Game, depth: usize, ply: i32, alpha: i32, beta: i32) -> i32 {
+        match child {
+            Final(g) => match g.winner() {
+                Some(p) if p == player => WIN - ply,
+                Some(_) => ply - WIN,
+                None => 0,
+            },
+            c if c.player_to_move() == Some(player) => self.value(c, depth, ply, alpha, beta),
+            c => -self.value(c, depth, ply, -beta, -alpha),
+        }
+    }
+}
+
+// wins and losses are stored counted from the position, so they can be found again at any ply
+fn to_table(v: i32, ply: i32) -> i32 {
+    if v > WIN - MAX_PLIES - 1 {
+        v + ply
+    } else if v < MAX_PLIES + 1 - WIN {
+        v - ply
+    } else {
+        v
+    }
+}
+
+fn from_table(v: i32, ply: i32) -> i32 {
+    if v > WIN - MAX_PLIES - 1 {
+        v - ply
+    } else if v < MAX_PLIES + 1 - WIN {
+        v + ply
+    } else {
+        v
+    }
+}
+
+fn score_from(v: i32) -> Score {
+    if v > WIN - MAX_PLIES - 1 {
+        Score::Win((WIN - v) as usize)
+    } else if v < MAX_PLIES + 1 - WIN {
+        Score::Loss((WIN + v) as usize)
+    } else {
+        Score::Draw
+    }
+}
+
+// placements that win on the spot come first, then passes that give no such placement away
+pub(crate) fn ordered_moves(game: &Game) -> Vec<Move> {
+    match game {
+        Pass(g) => {
+            let unsafe_pieces = g.unsafe_pieces();
+            let safe = g.legal_passes().filter(|p| !unsafe_pieces.contains(p));
+            safe.chain(unsafe_pieces.iter().copied()).map(Move::Pass).collect()
+        },
+        Place(g) => {
+            let wins = g.winning_squares();
+            let rest = g.legal_places().filter(|sq| !wins.contains(sq));
+            wins.iter().copied().chain(rest).map(|(h, v)| Move::Place(h, v)).collect()
+        },
+        Final(_) => vec![],
+    }
+}
@@ -1,70 +1,235 @@
+use crate::error::{InvalidIndex, MoveError, ParseSquareError, PositionError};
 use crate::piece::Piece;
-use std::fmt;
+use crate::symmetry::{self, Transform};
+use crate::zobrist;
+use std::{convert::TryFrom, fmt, str::FromStr};
 
 
+// squares are numbered row by row: square i is row i / 4, column i % 4
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "[[Option<Piece>; 4]; 4]", try_from = "[[Option<Piece>; 4]; 4]"))]
 pub struct Board {
-    // private field prevents ownership changes and therefor outside mutation
-    pieces: [[Option<Piece>; 4]; 4],
+    // private fields prevent ownership changes and therefor outside mutation
+    // the 4-bit code of the piece on square i is at bits 4i..4i+4. zero when empty.
+    pieces: u64,
+    // bit i is set when square i is occupied
+    occupied: u16,
+    // bit n is set when the piece with code n is on the board
+    placed: u16,
+    // the XOR of the Zobrist key of each piece on its square
+    zobrist: u64,
 }
 
+// the squares in each line of ALL_LINES, in the same order
+static LINE_SQUARES: [[usize; 4]; 10] = [
+    [0, 1, 2, 3],
+    [4, 5, 6, 7],
+    [8, 9, 10, 11],
+    [12, 13, 14, 15],
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [3, 6, 9, 12],
+];
+
+// the occupancy mask for each line of ALL_LINES, in the same order
+static LINE_MASKS: [u16; 10] = [
+    0x000F,
+    0x00F0,
+    0x0F00,
+    0xF000,
+    0x1111,
+    0x2222,
+    0x4444,
+    0x8888,
+    0x8421,
+    0x1248,
+];
+
 impl Board {
+    /// the inverse of raw. any arrangement is allowed as long as no piece appears twice.
+    pub fn from_squares(squares: [[Option<Piece>; 4]; 4]) -> Result<Board, PositionError> {
+        let mut board = new_board();
+        for (&square, p) in ALL_SQUARES.iter().zip(squares.iter().flatten()) {
+            if let Some(p) = *p {
+                if board.contains(&p) {
+                    return Err(PositionError::DuplicatePiece(p));
+                }
+                board = board.place_piece(square, p).unwrap();
+            }
+        }
+        Ok(board)
+    }
+
     pub fn raw(&self) -> [[Option<Piece>; 4]; 4] {
-        self.pieces.clone()
+        let mut raw = [[None; 4]; 4];
+        for (i, square) in raw.iter_mut().flatten().enumerate() {
+            *square = self.get_square(i);
+        }
+        raw
     }
 
     pub fn contains(&self, p: &Piece) -> bool {
-        let mut found = false;
-        for row in &self.pieces {
-            for piece in row {
-                piece.map(|x|
-                    if x == *p { found = true; }
-                );
-            };
-        };
-        found
+        self.placed & 1 << p.to_bits() != 0
     }
 
     pub fn piece_count(&self) -> usize {
-        self.pieces.iter().flatten().filter(|x| x.is_some()).count()
+        self.occupied.count_ones() as usize
     }
 
     pub fn is_full(&self) -> bool {
-        self.pieces.iter().flatten().all(|&x| x.is_some())
+        self.occupied == 0xFFFF
     }
 
-    pub fn place_piece(&self, square: (Idx, Idx), p: Piece) -> Option<Board> {
-        match self.get(square.0, square.1) {
-            None => {
-                let updated = &mut self.pieces.clone();
-                updated[square.0.to_i()][square.1.to_i()] = Some(p);
-                Some(Board { pieces: *updated })
-            },
-            Some(_) => None,
+    pub fn place_piece(&self, square: (Idx, Idx), p: Piece) -> Result<Board, MoveError> {
+        let i = square_index(square);
+        if self.occupied & 1 << i != 0 {
+            Err(MoveError::SquareOccupied)
+        } else {
+            Ok(Board {
+                pieces: self.pieces | (p.to_bits() as u64) << (4 * i),
+                occupied: self.occupied | 1 << i,
+                placed: self.placed | 1 << p.to_bits(),
+                zobrist: self.zobrist ^ zobrist::PIECE_KEYS[i][p.to_bits() as usize],
+            })
         }
     }
 
     pub fn get(&self, h: Idx, v: Idx) -> Option<Piece> {
-        self.pieces[h.to_i()][v.to_i()]
+        self.get_square(square_index((h, v)))
+    }
+
+    fn get_square(&self, i: usize) -> Option<Piece> {
+        if self.occupied & 1 << i == 0 {
+            None
+        } else {
+            Some(Piece::from_bits(self.code(i)))
+        }
+    }
+
+    // the code of the piece on square i. zero when empty.
+    pub(crate) fn code(&self, i: usize) -> u8 {
+        (self.pieces >> (4 * i) & 0xF) as u8
+    }
+
+    // a full line wins when the AND of its codes has a bit set (shared 1s)
+    // or the NOR of its codes has a bit set (shared 0s)
+    pub(crate) fn has_win(&self) -> bool {
+        LINE_MASKS.iter().zip(LINE_SQUARES.iter()).any(|(&mask, squares)| {
+            if self.occupied & mask != mask {
+                return false;
+            }
+            let and = self.code(squares[0]) & self.code(squares[1]) & self.code(squares[2]) & self.code(squares[3]);
+            let nor = !(self.code(squares[0]) | self.code(squares[1]) | self.code(squares[2]) | self.code(squares[3])) & 0xF;
+            and | nor != 0
+        })
+    }
+
+    // one bit for each empty square where p completes a line. only lines with
+    // three pieces can be completed, and p wins one when it shares a bit with all three.
+    pub(crate) fn winning_squares(&self, p: Piece) -> u16 {
+        let code = p.to_bits();
+        let mut squares = 0;
+        for (&mask, line) in LINE_MASKS.iter().zip(LINE_SQUARES.iter()) {
+            let open = mask & !self.occupied;
+            if open.count_ones() != 1 {
+                continue;
+            }
+            let (and, or) = line.iter()
+                .filter(|&&i| self.occupied & 1 << i != 0)
+                .fold((0xF, 0), |(and, or), &i| (and & self.code(i), or | self.code(i)));
+            if and & code | !(or | code) & 0xF != 0 {
+                squares |= open;
+            }
+        }
+        squares
+    }
+
+    /// the one board shared by every rearrangement of this board, and the transform
+    /// from this board to it. see `Transform`.
+    pub fn canonicalize(&self) -> (Board, Transform) {
+        let (board, _, t) = symmetry::canonical(self, None);
+        (board, t)
+    }
+
+    /// a hash of the pieces and where they are, updated with each piece placed.
+    /// see `Game::zobrist` to include the piece in hand and the player.
+    pub fn zobrist(&self) -> u64 {
+        self.zobrist
+    }
+
+    pub(crate) fn occupied(&self) -> u16 {
+        self.occupied
+    }
+
+    pub(crate) fn pieces(&self) -> u64 {
+        self.pieces
+    }
+
+    // the piece on square i moves to squares[i] and the piece with code n becomes codes[n]
+    pub(crate) fn rearranged(&self, squares: &[usize; 16], codes: &[u8; 16]) -> Board {
+        let mut board = new_board();
+        for i in (0..16).filter(|&i| self.occupied & 1 << i != 0) {
+            let (j, code) = (squares[i], codes[self.code(i) as usize]);
+            board.pieces |= (code as u64) << (4 * j);
+            board.occupied |= 1 << j;
+            board.placed |= 1 << code;
+            board.zobrist ^= zobrist::PIECE_KEYS[j][code as usize];
+        }
+        board
+    }
+
+    pub fn line(&self, l: Line) -> [Option<Piece>; 4] {
+        let squares = l.squares();
+        [
+            self.get(squares[0].0, squares[0].1),
+            self.get(squares[1].0, squares[1].1),
+            self.get(squares[2].0, squares[2].1),
+            self.get(squares[3].0, squares[3].1),
+        ]
     }
 }
 
 pub fn new_board() -> Board {
     Board {
-        pieces: [[None; 4]; 4],
+        pieces: 0,
+        occupied: 0,
+        placed: 0,
+        zobrist: 0,
+    }
+}
+
+impl From<Board> for [[Option<Piece>; 4]; 4] {
+    fn from(b: Board) -> [[Option<Piece>; 4]; 4] {
+        b.raw()
+    }
+}
+
+impl TryFrom<[[Option<Piece>; 4]; 4]> for Board {
+    type Error = PositionError;
+
+    fn try_from(squares: [[Option<Piece>; 4]; 4]) -> Result<Board, PositionError> {
+        Board::from_squares(squares)
     }
 }
 
+fn square_index(square: (Idx, Idx)) -> usize {
+    square.0.to_i() * 4 + square.1.to_i()
+}
+
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut s: String = "".to_owned();
-        for row in &self.pieces {
+        for row in &self.raw() {
             let mut row_s: String = "| ".to_owned();
             for p in row {
                 match p {
-                    None => row_s = row_s + "     | ",
+                    None => row_s += "     | ",
                     Some(p) => row_s = row_s + &p.to_string() + " | ",
                 };
             };
@@ -75,10 +240,47 @@ impl fmt::Display for Board {
     }
 }
 
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Line {
+    Row(Idx),
+    Col(Idx),
+    Diagonal,     // top left to bottom right
+    AntiDiagonal, // top right to bottom left
+}
+
+impl Line {
+    pub fn squares(&self) -> [(Idx, Idx); 4] {
+        use Idx::*;
+        match *self {
+            Line::Row(r) => [(r, I1), (r, I2), (r, I3), (r, I4)],
+            Line::Col(c) => [(I1, c), (I2, c), (I3, c), (I4, c)],
+            Line::Diagonal => [(I1, I1), (I2, I2), (I3, I3), (I4, I4)],
+            Line::AntiDiagonal => [(I1, I4), (I2, I3), (I3, I2), (I4, I1)],
+        }
+    }
+}
+
+pub static ALL_LINES: [Line; 10] = [
+    Line::Row(Idx::I1),
+    Line::Row(Idx::I2),
+    Line::Row(Idx::I3),
+    Line::Row(Idx::I4),
+    Line::Col(Idx::I1),
+    Line::Col(Idx::I2),
+    Line::Col(Idx::I3),
+    Line::Col(Idx::I4),
+    Line::Diagonal,
+    Line::AntiDiagonal,
+];
+
 // TODO Eq
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Idx {
     I1,
     I2,
@@ -95,6 +297,119 @@ impl Idx {
             Idx::I4 => 3,
         }
     }
+
+    /// None = i is not in 0..4
+    pub fn from_i(i: usize) -> Option<Idx> {
+        match i {
+            0 => Some(Idx::I1),
+            1 => Some(Idx::I2),
+            2 => Some(Idx::I3),
+            3 => Some(Idx::I4),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<usize> for Idx {
+    type Error = InvalidIndex;
+
+    fn try_from(i: usize) -> Result<Idx, InvalidIndex> {
+        Idx::from_i(i).ok_or(InvalidIndex(i))
+    }
+}
+
+// Squares are named by a column letter and a row number, so the top row reads
+// a1 b1 c1 d1 and the bottom row reads a4 b4 c4 d4.
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Square {
+    row: Idx,
+    col: Idx,
+}
+
+impl Square {
+    pub fn new(row: Idx, col: Idx) -> Square {
+        Square { row, col }
+    }
+
+    pub fn row(&self) -> Idx {
+        self.row
+    }
+
+    pub fn col(&self) -> Idx {
+        self.col
+    }
+
+    /// the square's position in ALL_SQUARES
+    pub fn index(&self) -> usize {
+        square_index((self.row, self.col))
+    }
+
+    /// None = i is not in 0..16
+    pub fn from_index(i: usize) -> Option<Square> {
+        ALL_SQUARES.get(i).map(|&sq| Square::from(sq))
+    }
+}
+
+impl From<(Idx, Idx)> for Square {
+    fn from(sq: (Idx, Idx)) -> Square {
+        Square::new(sq.0, sq.1)
+    }
+}
+
+impl From<Square> for (Idx, Idx) {
+    fn from(sq: Square) -> (Idx, Idx) {
+        (sq.row, sq.col)
+    }
+}
+
+impl TryFrom<usize> for Square {
+    type Error = InvalidIndex;
+
+    fn try_from(i: usize) -> Result<Square, InvalidIndex> {
+        Square::from_index(i).ok_or(InvalidIndex(i))
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let col = match self.col {
+            Idx::I1 => 'a',
+            Idx::I2 => 'b',
+            Idx::I3 => 'c',
+            Idx::I4 => 'd',
+        };
+        write!(f, "{}{}", col, self.row.to_i() + 1)
+    }
+}
+
+// case-insensitive: "A1" parses as a1
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    fn from_str(s: &str) -> Result<Square, ParseSquareError> {
+        let chars: Vec<char> = s.chars().collect();
+        if chars.len() != 2 {
+            return Err(ParseSquareError::Length(chars.len()));
+        }
+        let col = match chars[0].to_ascii_lowercase() {
+            'a' => Idx::I1,
+            'b' => Idx::I2,
+            'c' => Idx::I3,
+            'd' => Idx::I4,
+            c   => return Err(ParseSquareError::Column(c)),
+        };
+        let row = match chars[1] {
+            '1' => Idx::I1,
+            '2' => Idx::I2,
+            '3' => Idx::I3,
+            '4' => Idx::I4,
+            r   => return Err(ParseSquareError::Row(r)),
+        };
+        Ok(Square::new(row, col))
+    }
 }
 
 pub static ALL_SQUARES: [(Idx, Idx); 16] = [
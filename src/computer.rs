@@ -0,0 +1,202 @@
+use crate::common::*;
+use crate::local::{self, LocalApp};
+use quarto::*;
+use std::{
+    cmp::min,
+    io,
+    time::Duration,
+};
+use termion::{
+    color,
+    event::Key,
+};
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum Opponent {
+    Level(Difficulty),
+    Random,
+    AlphaBeta(AlphaBeta),
+    Mcts(Mcts),
+}
+
+impl Opponent {
+    fn agent(self, seed: u64) -> Box<dyn Agent> {
+        match self {
+            Opponent::Level(difficulty) => Box::new(difficulty.agent(seed)),
+            Opponent::Random => Box::new(RandomAgent::new(seed)),
+            Opponent::AlphaBeta(engine) => Box::new(engine),
+            Opponent::Mcts(engine) => Box::new(Mcts { seed, ..engine }),
+        }
+    }
+}
+
+// searches as deep as it can in under a second
+const ALPHA_BETA: AlphaBeta = AlphaBeta { depth: 32, node_limit: Some(1_000_000) };
+
+// the seed is replaced with the game's own
+const MCTS: Mcts = Mcts { iterations: 100_000, time_limit: Some(Duration::from_secs(1)), seed: 0 };
+
+static MENU: [(Opponent, &str); 3] = [
+    (Opponent::Random,               "          Random Moves         "),
+    (Opponent::AlphaBeta(ALPHA_BETA), "           Alpha-Beta          "),
+    (Opponent::Mcts(MCTS),           "       Monte Carlo Search      "),
+];
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct MenuState {
+    // 0 is the difficulty level, then each index into MENU
+    cursor: usize,
+    difficulty: Difficulty,
+    opponent: Option<Opponent>,
+}
+
+impl MenuState {
+    pub fn new(difficulty: Difficulty) -> MenuState {
+        MenuState { cursor: 0, difficulty, opponent: None }
+    }
+
+    fn choice(&self) -> Opponent {
+        match self.cursor {
+            0 => Opponent::Level(self.difficulty),
+            i => MENU[i - 1].0,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum MenuAction {
+    CursorUp,
+    CursorDown,
+    Submit,
+    Quit,
+    Idle,
+}
+
+pub struct OpponentApp {}
+
+impl App for OpponentApp {
+    type State = MenuState;
+    type Action = MenuAction;
+    type Output = Opponent;
+
+    fn initial_state() -> Self::State {
+        MenuState::new(Difficulty::Casual)
+    }
+
+    fn write_state<W: io::Write>(f: &mut W, state: Self::State) {
+        let mut cursor: (u16, u16) = (1, 2);
+
+        write_banner_at(cursor, f);
+        cursor = (3, 8);
+
+        let name = state.difficulty.to_string();
+        let level = format!("{:^31}", format!("{}{} Level", name[..1].to_uppercase(), &name[1..]));
+        let labels = std::iter::once(level.as_str()).chain(MENU.iter().map(|(_, label)| *label));
+        for (i, label) in labels.enumerate() {
+            cursor.1 += 1;
+            if state.cursor == i {
+                f.write_fmt(format_args!("{}{}{}{}",
+                    termion::cursor::Goto(cursor.0, cursor.1),
+                    color::Bg(color::AnsiValue::grayscale(12)),
+                    label,
+                    color::Bg(color::Reset)
+                )).unwrap();
+            } else {
+                write_at(cursor, f, label);
+            }
+        }
+
+        cursor.1 += 2;
+        write_at(cursor, f, "         - q to quit -         ");
+    }
+
+    fn action_from(key: Option<std::result::Result<termion::event::Key, std::io::Error>>) -> Self::Action {
+        match key {
+            Some(Ok(key)) => match key {
+                Key::Char('q')  => MenuAction::Quit,
+                Key::Char('\n') => MenuAction::Submit,
+                Key::Up         => MenuAction::CursorUp,
+                Key::Down       => MenuAction::CursorDown,
+                _               => MenuAction::Idle,
+            },
+            // throws errors away
+            _ => MenuAction::Idle,
+        }
+    }
+
+    fn step(state: Self::State, action: Self::Action) -> Option<Self::State> {
+        match action {
+            MenuAction::Quit => None,
+            MenuAction::Idle => Some(state),
+            MenuAction::Submit => Some(MenuState { opponent: Some(state.choice()), ..state }),
+            MenuAction::CursorUp => Some(MenuState { cursor: state.cursor.saturating_sub(1), ..state }),
+            MenuAction::CursorDown => Some(MenuState { cursor: min(MENU.len(), state.cursor + 1), ..state }),
+        }
+    }
+
+    fn output_from(state: Self::State) -> Option<Self::Output> {
+        state.opponent
+    }
+}
+
+// the human always plays P1 and makes the first pass
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct State {
+    local: local::State,
+    opponent: Opponent,
+    // seeds the computer's next move
+    seed: u64,
+}
+
+impl State {
+    pub fn new(opponent: Opponent, seed: u64) -> State {
+        State { local: LocalApp::initial_state(), opponent, seed }
+    }
+}
+
+pub struct ComputerApp {}
+
+impl App for ComputerApp {
+    type State = State;
+    type Action = local::Action;
+    type Output = local::State;
+
+    fn initial_state() -> Self::State {
+        State::new(Opponent::Random, 0)
+    }
+
+    fn write_state<W: io::Write>(f: &mut W, state: Self::State) {
+        LocalApp::write_state(f, state.local)
+    }
+
+    fn action_from(key: Option<std::result::Result<termion::event::Key, std::io::Error>>) -> Self::Action {
+        LocalApp::action_from(key)
+    }
+
+    fn step(state: Self::State, action: Self::Action) -> Option<Self::State> {
+        match (action, state.local.game().player_to_move()) {
+            (local::Action::Quit, _) => None,
+            // the computer moves on the first tick of its turn, whatever key was pressed
+            (_, Some(P2)) => {
+                let game = state.local.game();
+                let m = state.opponent.agent(state.seed).choose_move(&game)?;
+                // the agent only chooses legal moves
+                let local = state.local.play_move(m).ok()?;
+                Some(State { local, opponent: state.opponent, seed: state.seed.wrapping_add(1) })
+            },
+            (a, _) => LocalApp::step(state.local, a).map(|local| State { local, ..state }),
+        }
+    }
+
+    fn output_from(state: Self::State) -> Option<Self::Output> {
+        LocalApp::output_from(state.local)
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{Game, Move, PassGame, PlaceGame};
+use crate::agent::{chosen_pass, chosen_place, Agent};
+use crate::alpha_beta::AlphaBeta;
+use crate::board::Idx;
+use crate::error::ParseDifficultyError;
+use crate::piece::Piece;
+use crate::solver::Solver;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{fmt, str::FromStr};
+
+/// How well the computer plays, from weakest to strongest.
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(PartialOrd, Ord)]
+#[derive(Debug)]
+pub enum Difficulty {
+    Beginner,
+    Casual,
+    Strong,
+    /// never blunders and plays perfectly once few enough squares are empty, but before
+    /// then its search stops at a node limit, so it can still lose
+    Expert,
+}
+
+pub static ALL_DIFFICULTIES: [Difficulty; 4] = [
+    Difficulty::Beginner,
+    Difficulty::Casual,
+    Difficulty::Strong,
+    Difficulty::Expert,
+];
+
+impl Difficulty {
+    /// the search used for every move that is not a blunder
+    pub fn engine(self) -> AlphaBeta {
+        match self {
+            Difficulty::Beginner => AlphaBeta { depth: 1, node_limit: None },
+            Difficulty::Casual => AlphaBeta { depth: 2, node_limit: None },
+            Difficulty::Strong => AlphaBeta { depth: 4, node_limit: Some(20_000) },
+            Difficulty::Expert => AlphaBeta { depth: 32, node_limit: Some(200_000) },
+        }
+    }
+
+    /// games with at most this many empty squares are solved exactly instead of searched
+    pub fn solved_empty_squares(self) -> usize {
+        match self {
+            Difficulty::Expert => 8,
+            _ => 0,
+        }
+    }
+
+    /// the chance in 100 of playing a random legal move instead
+    pub fn blunder_percent(self) -> u32 {
+        match self {
+            Difficulty::Beginner => 40,
+            Difficulty::Casual => 15,
+            Difficulty::Strong => 3,
+            Difficulty::Expert => 0,
+        }
+    }
+
+    /// The same seed always plays the same moves.
+    pub fn agent(self, seed: u64) -> DifficultyAgent {
+        DifficultyAgent {
+            difficulty: self,
+            solver: Solver::new(self.solved_empty_squares()),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Difficulty::Beginner => "beginner",
+            Difficulty::Casual => "casual",
+            Difficulty::Strong => "strong",
+            Difficulty::Expert => "expert",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+// case-insensitive: "Casual" parses as casual
+impl FromStr for Difficulty {
+    type Err = ParseDifficultyError;
+
+    fn from_str(s: &str) -> Result<Difficulty, ParseDifficultyError> {
+        ALL_DIFFICULTIES.iter()
+            .copied()
+            .find(|d| d.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseDifficultyError(s.to_string()))
+    }
+}
+
+/// Plays at one difficulty.
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct DifficultyAgent {
+    difficulty: Difficulty,
+    solver: Solver,
+    rng: StdRng,
+}
+
+impl DifficultyAgent {
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    fn choose(&mut self, game: &Game) -> Option<Move> {
+        if self.rng.gen_ratio(self.difficulty.blunder_percent(), 100) {
+            return game.legal_moves().choose(&mut self.rng).copied();
+        }
+        match self.solver.solve(game) {
+            Some(solution) => Some(solution.best),
+            None => self.difficulty.engine().search(game).map(|a| a.best),
+        }
+    }
+}
+
+impl Agent for DifficultyAgent {
+    fn choose_pass(&mut self, game: &PassGame) -> Piece {
+        let chosen = self.choose(&game.to_game());
+        chosen_pass(game, chosen)
+    }
+
+    fn choose_place(&mut self, game: &PlaceGame) -> (Idx, Idx) {
+        let chosen = self.choose(&game.to_game());
+        chosen_place(game, chosen)
+    }
+}
@@ -0,0 +1,288 @@
+use crate::Player;
+use crate::piece::Piece;
+use std::{error, fmt};
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum MoveError {
+    /// the piece has already been placed
+    PieceOnBoard,
+    /// the piece is the one currently being passed
+    PieceInHand,
+    SquareOccupied,
+    GameOver,
+    /// a piece must be passed before anything can be placed
+    ExpectedPass,
+    /// the passed piece must be placed before another can be passed
+    ExpectedPlace,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            MoveError::PieceOnBoard => "that piece is already on the board",
+            MoveError::PieceInHand => "that piece is already being passed",
+            MoveError::SquareOccupied => "that square is occupied",
+            MoveError::GameOver => "the game is already over",
+            MoveError::ExpectedPass => "a piece must be passed first",
+            MoveError::ExpectedPlace => "the passed piece must be placed first",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl error::Error for MoveError {}
+
+/// a piece code outside of 0..16
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct InvalidPieceCode(pub u8);
+
+impl fmt::Display for InvalidPieceCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is not a piece code. piece codes are 0 through 15", self.0)
+    }
+}
+
+impl error::Error for InvalidPieceCode {}
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum ParsePieceError {
+    /// piece notation is exactly four letters. holds the number found.
+    Length(usize),
+    /// the letter at `index` is not valid for that position
+    Letter { index: usize, found: char },
+}
+
+impl fmt::Display for ParsePieceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParsePieceError::Length(n) =>
+                write!(f, "a piece is written as four letters like WTRF, found {}", n),
+            ParsePieceError::Letter { index, found } => {
+                let expected = match index {
+                    0 => "color W or B",
+                    1 => "height T or S",
+                    2 => "shape R or Q",
+                    _ => "top F or H",
+                };
+                write!(f, "expected {} at position {}, found '{}'", expected, index + 1, found)
+            },
+        }
+    }
+}
+
+impl error::Error for ParsePieceError {}
+
+/// a row, column or square index out of range
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct InvalidIndex(pub usize);
+
+impl fmt::Display for InvalidIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is out of range for the board", self.0)
+    }
+}
+
+impl error::Error for InvalidIndex {}
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum ParseSquareError {
+    /// square notation is exactly a letter and a number. holds the number of characters found.
+    Length(usize),
+    Column(char),
+    Row(char),
+}
+
+impl fmt::Display for ParseSquareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseSquareError::Length(n) =>
+                write!(f, "a square is written as a column and a row like a1, found {} characters", n),
+            ParseSquareError::Column(c) => write!(f, "expected a column a through d, found '{}'", c),
+            ParseSquareError::Row(r) => write!(f, "expected a row 1 through 4, found '{}'", r),
+        }
+    }
+}
+
+impl error::Error for ParseSquareError {}
+
+/// a difficulty other than beginner, casual, strong or expert. holds the text found.
+#[derive(Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct ParseDifficultyError(pub String);
+
+impl fmt::Display for ParseDifficultyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected beginner, casual, strong or expert, found '{}'", self.0)
+    }
+}
+
+impl error::Error for ParseDifficultyError {}
+
+/// a position that could not have been reached by playing the game
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum PositionError {
+    DuplicatePiece(Piece),
+    PieceInHandOnBoard(Piece),
+    /// a piece must be in hand to be placed
+    MissingPieceInHand,
+    /// only a piece that is about to be placed can be in hand
+    UnexpectedPieceInHand,
+    /// the board has a winning line but the game is not over
+    AlreadyWon,
+    /// the board is full but the game is not over
+    BoardFull,
+    /// the game is over but the board has no winning line and is not full
+    NotOver,
+    /// the board has winning lines that could not all have been made by the last piece placed
+    SeparateWins,
+    /// the number of pieces on the board says the other player moves next, or for a final
+    /// game moved last. holds the player given.
+    WrongPlayer(Player),
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PositionError::DuplicatePiece(p) => write!(f, "{} is on the board more than once", p),
+            PositionError::PieceInHandOnBoard(p) => write!(f, "{} is both in hand and on the board", p),
+            PositionError::MissingPieceInHand => write!(f, "a piece must be in hand to place one"),
+            PositionError::UnexpectedPieceInHand => write!(f, "a piece can only be in hand when one is being placed"),
+            PositionError::AlreadyWon => write!(f, "the board has a winning line but the game is not over"),
+            PositionError::BoardFull => write!(f, "the board is full but the game is not over"),
+            PositionError::NotOver => write!(f, "the game is over without a winning line or a full board"),
+            PositionError::SeparateWins => write!(f, "the winning lines do not share the last piece placed"),
+            PositionError::WrongPlayer(_) => write!(f, "the number of pieces on the board says it is the other player's turn"),
+        }
+    }
+}
+
+impl error::Error for PositionError {}
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum ParseGameError {
+    /// a position is four space separated fields. holds the number found.
+    Fields(usize),
+    /// the board is four rows separated by '/'. holds the number found.
+    Rows(usize),
+    /// the row at this index does not describe exactly four squares
+    Row(usize),
+    Piece(ParsePieceError),
+    /// the phase is not one of pass, place or final
+    Phase,
+    /// the player is not one of P1 or P2
+    Player,
+    Position(PositionError),
+}
+
+impl fmt::Display for ParseGameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseGameError::Fields(n) =>
+                write!(f, "expected board, phase, piece in hand and player separated by spaces, found {} fields", n),
+            ParseGameError::Rows(n) => write!(f, "expected 4 rows separated by '/', found {}", n),
+            ParseGameError::Row(i) => write!(f, "row {} does not describe exactly 4 squares", i + 1),
+            ParseGameError::Piece(e) => write!(f, "{}", e),
+            ParseGameError::Phase => write!(f, "expected the phase to be pass, place or final"),
+            ParseGameError::Player => write!(f, "expected the player to be P1 or P2"),
+            ParseGameError::Position(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for ParseGameError {}
+
+impl From<ParsePieceError> for ParseGameError {
+    fn from(e: ParsePieceError) -> ParseGameError {
+        ParseGameError::Piece(e)
+    }
+}
+
+impl From<PositionError> for ParseGameError {
+    fn from(e: PositionError) -> ParseGameError {
+        ParseGameError::Position(e)
+    }
+}
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum ReadGameError {
+    /// the line with this number is not a [Name "value"] tag
+    Tag(usize),
+    Piece(ParsePieceError),
+    Square(ParseSquareError),
+    /// the move at this ply is not legal
+    Move(usize, MoveError),
+    /// the stated result does not match the moves
+    Result,
+    /// there are moves after the result
+    AfterResult,
+}
+
+impl fmt::Display for ReadGameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadGameError::Tag(n) => write!(f, "line {} is not a tag like [Name \"value\"]", n),
+            ReadGameError::Piece(e) => write!(f, "{}", e),
+            ReadGameError::Square(e) => write!(f, "{}", e),
+            ReadGameError::Move(ply, e) => write!(f, "move {} is not legal: {}", ply + 1, e),
+            ReadGameError::Result => write!(f, "the result does not match the moves"),
+            ReadGameError::AfterResult => write!(f, "found moves after the result"),
+        }
+    }
+}
+
+impl error::Error for ReadGameError {}
+
+/// a serialized record whose moves cannot be replayed
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum ReplayError {
+    /// the move at this ply is not legal
+    Move(usize, MoveError),
+    /// the current ply is past the last recorded move
+    Ply(usize),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Move(ply, e) => write!(f, "move {} is not legal: {}", ply + 1, e),
+            ReplayError::Ply(ply) => write!(f, "ply {} is past the last recorded move", ply),
+        }
+    }
+}
+
+impl error::Error for ReplayError {}
+
+/// a tag name that is empty, has characters other than letters, digits and underscores,
+/// or is one the header always writes itself. holds the name.
+#[derive(Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct InvalidTagName(pub String);
+
+impl fmt::Display for InvalidTagName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a tag name. names are letters, digits and underscores, \
+            other than Player1, Player2, Date, Variant and Result", self.0)
+    }
+}
+
+impl error::Error for InvalidTagName {}
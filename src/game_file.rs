@@ -0,0 +1,282 @@
+use crate::{Game, Move, Outcome, Final, P1, P2};
+use crate::board::Square;
+use crate::error::{InvalidTagName, MoveError, ParsePieceError, ParseSquareError, ReadGameError};
+use crate::piece::Piece;
+use crate::record::{new_record, GameRecord};
+use std::{fmt, str::FromStr};
+
+// A saved game, similar to chess's PGN. Tags come first, one per line, followed by a
+// blank line and the moves. Each numbered move is a pass in piece notation and the
+// place of that piece in square notation. The moves end with the result: 1-0 for a
+// P1 win, 0-1 for a P2 win, 1/2-1/2 for a draw, or * for an unfinished game. In tag
+// values, \ and " are escaped with a backslash and control characters as \n, \r, \t
+// or \u{..}.
+//
+//   [Player1 "Ann"]
+//   [Player2 "Bo"]
+//   [Date "2021.01.30"]
+//   [Variant "standard"]
+//   [Result "0-1"]
+//
+//   1. WTRF a1 2. BSQH a2 3. WTRH b1 4. BSQF b2 5. WTQF c1 6. BSRH a3 7. WTQH d1 0-1
+
+// lines of moves are wrapped to at most this width
+static LINE_WIDTH: usize = 80;
+
+// the tags every header has, in the order they are written
+static RESERVED_TAGS: [&str; 5] = ["Player1", "Player2", "Date", "Variant", "Result"];
+
+#[derive(Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct Header {
+    pub player1: String,
+    pub player2: String,
+    /// YYYY.MM.DD with ?? for unknown parts
+    pub date: String,
+    pub variant: String,
+    /// any other tags, in the order they appear
+    pub other: Tags,
+}
+
+/// Tags beyond the ones every header has. Only names that can be written and read back
+/// as the same tag are accepted.
+#[derive(Clone, Default)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct Tags {
+    tags: Vec<(String, String)>,
+}
+
+impl Tags {
+    /// Adds a tag after the others. The name must be letters, digits and underscores, and
+    /// not one of Player1, Player2, Date, Variant or Result.
+    pub fn push(&mut self, name: &str, value: &str) -> Result<(), InvalidTagName> {
+        if !is_tag_name(name) || RESERVED_TAGS.contains(&name) {
+            return Err(InvalidTagName(String::from(name)));
+        }
+        self.tags.push((String::from(name), String::from(value)));
+        Ok(())
+    }
+
+    /// the value of the first tag with this name
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.tags.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+impl Default for Header {
+    fn default() -> Header {
+        Header {
+            player1: String::from("?"),
+            player2: String::from("?"),
+            date: String::from("????.??.??"),
+            variant: String::from("standard"),
+            other: Tags::default(),
+        }
+    }
+}
+
+/// A complete game with its header. The result is always taken from the game itself.
+/// Only the moves up to the record's current ply are saved.
+#[derive(Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct GameFile {
+    pub header: Header,
+    pub record: GameRecord,
+}
+
+impl fmt::Display for GameFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let result = result_notation(self.record.game());
+        write_tag(f, "Player1", &self.header.player1)?;
+        write_tag(f, "Player2", &self.header.player2)?;
+        write_tag(f, "Date", &self.header.date)?;
+        write_tag(f, "Variant", &self.header.variant)?;
+        write_tag(f, "Result", result)?;
+        for (name, value) in self.header.other.iter() {
+            write_tag(f, name, value)?;
+        }
+        writeln!(f)?;
+
+        let mut tokens: Vec<String> = self.record.history().chunks(2).enumerate()
+            .map(|(i, pair)| {
+                let moves: Vec<String> = pair.iter().map(|&m| move_notation(m)).collect();
+                format!("{}. {}", i + 1, moves.join(" "))
+            })
+            .collect();
+        tokens.push(String::from(result));
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + 1 + token.len() > LINE_WIDTH {
+                writeln!(f, "{}", line)?;
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line += &token;
+        }
+        writeln!(f, "{}", line)
+    }
+}
+
+impl FromStr for GameFile {
+    type Err = ReadGameError;
+
+    fn from_str(s: &str) -> Result<GameFile, ReadGameError> {
+        let mut header = Header::default();
+        let mut result = None;
+        let mut lines = s.lines().enumerate().peekable();
+
+        // tags run until the first line that is not one
+        while let Some((n, line)) = lines.peek() {
+            let line = line.trim();
+            if line.is_empty() {
+                lines.next();
+                continue;
+            }
+            if !line.starts_with('[') {
+                break;
+            }
+            let (name, value) = parse_tag(line).ok_or(ReadGameError::Tag(n + 1))?;
+            match name.as_str() {
+                "Player1" => header.player1 = value,
+                "Player2" => header.player2 = value,
+                "Date" => header.date = value,
+                "Variant" => header.variant = value,
+                "Result" => result = Some(value),
+                _ => header.other.push(&name, &value).map_err(|_| ReadGameError::Tag(n + 1))?,
+            }
+            lines.next();
+        }
+
+        let mut record = new_record();
+        let mut end = None;
+        for token in lines.flat_map(|(_, line)| line.split_whitespace()) {
+            if end.is_some() {
+                return Err(ReadGameError::AfterResult);
+            }
+            match token {
+                "1-0" | "0-1" | "1/2-1/2" | "*" => end = Some(token),
+                t if t.ends_with('.') && t[..t.len() - 1].parse::<usize>().is_ok() => {},
+                t => {
+                    let m = match record.game() {
+                        Final(_) => return Err(ReadGameError::Move(record.ply(), MoveError::GameOver)),
+                        g if g.get_passed_piece().is_none() => Move::Pass(t.parse::<Piece>()?),
+                        _ => {
+                            let square = t.parse::<Square>()?;
+                            Move::Place(square.row(), square.col())
+                        },
+                    };
+                    record.play(m).map_err(|e| ReadGameError::Move(record.ply(), e))?;
+                },
+            }
+        }
+
+        let actual = result_notation(record.game());
+        let stated = [result.as_deref(), end];
+        if stated.iter().flatten().any(|&r| r != actual) {
+            return Err(ReadGameError::Result);
+        }
+
+        Ok(GameFile { header, record })
+    }
+}
+
+impl From<ParsePieceError> for ReadGameError {
+    fn from(e: ParsePieceError) -> ReadGameError {
+        ReadGameError::Piece(e)
+    }
+}
+
+impl From<ParseSquareError> for ReadGameError {
+    fn from(e: ParseSquareError) -> ReadGameError {
+        ReadGameError::Square(e)
+    }
+}
+
+fn result_notation(game: Game) -> &'static str {
+    match game.outcome() {
+        Some(Outcome::Win(P1)) => "1-0",
+        Some(Outcome::Win(P2)) => "0-1",
+        Some(Outcome::Draw) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+fn move_notation(m: Move) -> String {
+    match m {
+        Move::Pass(p) => p.to_string(),
+        Move::Place(h, v) => Square::new(h, v).to_string(),
+    }
+}
+
+// control characters are escaped so that a value never spans lines
+fn write_tag(f: &mut fmt::Formatter, name: &str, value: &str) -> fmt::Result {
+    let mut escaped = String::new();
+    for c in value.chars() {
+        match c {
+            '\\' => escaped += "\\\\",
+            '"' => escaped += "\\\"",
+            '\n' => escaped += "\\n",
+            '\r' => escaped += "\\r",
+            '\t' => escaped += "\\t",
+            c if c.is_control() => escaped += &format!("\\u{{{:x}}}", c as u32),
+            c => escaped.push(c),
+        }
+    }
+    writeln!(f, "[{} \"{}\"]", name, escaped)
+}
+
+// None = not a [Name "value"] tag
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let space = inner.find(' ')?;
+    let name = &inner[..space];
+    let quoted = inner[space..].trim();
+    let value = quoted.strip_prefix('"')?.strip_suffix('"')?;
+    if !is_tag_name(name) {
+        return None;
+    }
+
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'n' => unescaped.push('\n'),
+                'r' => unescaped.push('\r'),
+                't' => unescaped.push('\t'),
+                'u' => {
+                    let hex = chars.as_str().strip_prefix('{')?;
+                    let close = hex.find('}')?;
+                    unescaped.push(std::char::from_u32(u32::from_str_radix(&hex[..close], 16).ok()?)?);
+                    chars = hex[close + 1..].chars();
+                },
+                c => unescaped.push(c),
+            },
+            '"' => return None,
+            c => unescaped.push(c),
+        }
+    }
+    Some((String::from(name), unescaped))
+}
+
+fn is_tag_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
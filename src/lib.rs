@@ -1,9 +1,35 @@
+mod agent;
+mod alpha_beta;
 mod board;
+mod difficulty;
+mod error;
+mod game_file;
+mod mcts;
+mod net;
+mod notation;
 mod piece;
+mod record;
+#[cfg(feature = "serde")]
+mod serialize;
+mod solver;
+mod symmetry;
+mod transposition;
+mod zobrist;
 
 // re-exorts
+pub use agent::{
+    Agent,
+    RandomAgent,
+    play_out,
+};
+pub use alpha_beta::{
+    AlphaBeta,
+    Analysis,
+    Score,
+};
 pub use piece::{
     Piece,
+    Attribute,
     Color, Shape, Height, Top,
     ALL_PIECES,
     WTRF, WTRH, WTQF, WTQH, WSRF, WSRH, WSQF, WSQH, BTRF, BTRH, BTQF, BTQH, BSRF, BSRH, BSQF, BSQH
@@ -12,41 +38,152 @@ pub use board::{
     Board, 
     Idx,
     Idx::{I1, I2, I3, I4},
+    Line,
+    Square,
     new_board,
+    ALL_LINES,
     ALL_SQUARES
 };
+pub use difficulty::{
+    Difficulty,
+    DifficultyAgent,
+    ALL_DIFFICULTIES,
+};
+pub use error::{
+    InvalidIndex,
+    InvalidPieceCode,
+    InvalidTagName,
+    MoveError,
+    ParseDifficultyError,
+    ParseGameError,
+    ParsePieceError,
+    ParseSquareError,
+    PositionError,
+    ReadGameError,
+    ReplayError,
+};
+pub use game_file::{
+    GameFile,
+    Header,
+    Tags,
+};
+pub use mcts::{
+    Candidate,
+    Mcts,
+};
+pub use net::{
+    Connection,
+    Host,
+};
+pub use record::{
+    GameRecord,
+    new_record,
+};
+pub use solver::{
+    Solution,
+    Solver,
+    Value,
+};
+pub use symmetry::{
+    Transform,
+    all_transforms,
+};
+pub use transposition::{
+    Bound,
+    Entry,
+    Replace,
+    TranspositionTable,
+};
 pub use self::Game::{Pass, Place, Final};
+pub use self::Player::{P1, P2};
 
 // local imports
 use either::Either;
-use std::collections::HashMap;
 use piece::Attribute::*;
 
+// P1 always makes the first pass
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Player {
+    P1,
+    P2,
+}
+
+impl Player {
+    pub fn switch(&self) -> Player {
+        match self {
+            P1 => P2,
+            P2 => P1,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Outcome {
+    Win(Player),
+    Draw,
+}
+
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "crate::serialize::RawPassGame"))]
 pub struct PassGame {
     board: Board,
+    player: Player,
 }
 
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "crate::serialize::RawPlaceGame"))]
 pub struct PlaceGame {
     board: Board,
-    passed: Piece
+    passed: Piece,
+    player: Player,
 }
 
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "crate::serialize::RawFinalGame"))]
 pub struct FinalGame {
     board: Board,
+    // the player who placed the last piece
+    player: Player,
+}
+
+// a line of four pieces that all share at least one attribute
+#[derive(Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Win {
+    pub line: Line,
+    pub attributes: Vec<Attribute>,
 }
 
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Move {
+    Pass(Piece),
+    Place(Idx, Idx),
+}
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Game {
     Pass(PassGame),
     Place(PlaceGame),
@@ -70,21 +207,24 @@ impl Game {
     }
 
     pub fn is_final(&self) -> bool {
-        match self {
-            Final(_) => true,
-            _        => false,
-        }
+        matches!(self, Final(_))
     }
 
+    /// true only for a final game that was not won, even with a full board
     pub fn is_tie(&self) -> bool {
+        self.outcome() == Some(Outcome::Draw)
+    }
+
+    /// None = the game is not over yet
+    pub fn outcome(&self) -> Option<Outcome> {
         match self {
-            g @ Final(_) => g.board().is_full(),
-            _ => false
+            Final(g) => Some(g.outcome()),
+            _ => None,
         }
     }
     
     pub fn has_win(&self) -> bool {
-        has_win(&self.board())
+        has_win(self.board())
     }
 
     pub fn piece_count(&self) -> usize {
@@ -101,12 +241,98 @@ impl Game {
             _ => None,
         }
     }
+
+    pub fn apply(self, m: Move) -> Result<Game, MoveError> {
+        match (self, m) {
+            (Final(_), _) => Err(MoveError::GameOver),
+            (Pass(g), Move::Pass(p)) => g.pass(p).map(|x| x.to_game()),
+            (Pass(_), Move::Place(..)) => Err(MoveError::ExpectedPass),
+            (Place(g), Move::Place(h, v)) => g.place((h, v)).map(|y| match y {
+                Either::Left(x)  => x.to_game(),
+                Either::Right(x) => x.to_game(),
+            }),
+            (Place(g), Move::Pass(p)) if p == g.passed() => Err(MoveError::PieceInHand),
+            (Place(_), Move::Pass(_)) => Err(MoveError::ExpectedPlace),
+        }
+    }
+
+    /// Builds a game from any board and piece in hand. The game is final when the board
+    /// has a win or is full, is a place when a piece is in hand, and is a pass otherwise.
+    /// The player is the one to move, or for a final game the one who moved last.
+    pub fn from_position(board: Board, hand: Option<Piece>, player: Player) -> Result<Game, PositionError> {
+        let is_final = has_win(&board) || board.is_full();
+        game_from_parts(board, hand, is_final, player)
+    }
+
+    /// The one game shared by every rearrangement of this game, and the transform from
+    /// this game to it. See `Transform` for the rearrangements.
+    pub fn canonicalize(&self) -> (Game, Transform) {
+        let (_, _, t) = symmetry::canonical(self.board(), self.get_passed_piece());
+        (t.map_game(self), t)
+    }
+
+    /// A hash of the whole game: the board, the piece in hand and the player. It is
+    /// kept up to date with each move rather than computed from scratch.
+    pub fn zobrist(&self) -> u64 {
+        let hand = self.get_passed_piece().map_or(0, |p| zobrist::HAND_KEYS[p.to_bits() as usize]);
+        let player = match self {
+            Pass(g)  => g.player,
+            Place(g) => g.player,
+            Final(g) => g.player,
+        };
+        let player = if player == P2 { zobrist::P2_KEY } else { 0 };
+        self.board().zobrist() ^ hand ^ player
+    }
+
+    /// empty once the game is over
+    pub fn legal_moves(&self) -> Vec<Move> {
+        match self {
+            Pass(g)  => g.legal_passes().map(Move::Pass).collect(),
+            Place(g) => g.legal_places().map(|(h, v)| Move::Place(h, v)).collect(),
+            Final(_) => vec![],
+        }
+    }
+
+    /// None = the game is over
+    pub fn player_to_move(&self) -> Option<Player> {
+        match self {
+            Pass(g)  => Some(g.player()),
+            Place(g) => Some(g.player()),
+            Final(_) => None,
+        }
+    }
 }
 
 impl FinalGame {
     pub fn to_game(self) -> Game {
         Final(self)
     }
+
+    /// the player who placed the last piece
+    pub fn last_player(&self) -> Player {
+        self.player
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        if has_win(&self.board) {
+            Outcome::Win(self.player)
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// None = the game is a draw
+    pub fn winner(&self) -> Option<Player> {
+        match self.outcome() {
+            Outcome::Win(p) => Some(p),
+            Outcome::Draw => None,
+        }
+    }
+
+    /// every winning line on the board. empty for a tie.
+    pub fn wins(&self) -> Vec<Win> {
+        wins(&self.board)
+    }
 }
 
 impl PassGame {
@@ -114,11 +340,32 @@ impl PassGame {
         Pass(self)
     }
 
-    pub fn pass(self, p: Piece) -> Option<PlaceGame> {
-        if self.to_game().contains(&p) {
-            None
+    /// the player choosing a piece to pass
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    /// every piece not yet on the board
+    pub fn legal_passes(&self) -> impl Iterator<Item = Piece> {
+        let board = self.board;
+        ALL_PIECES.iter().copied().filter(move |p| !board.contains(p))
+    }
+
+    /// every piece left to pass that the other player could place to win at once
+    pub fn unsafe_pieces(&self) -> Vec<Piece> {
+        self.legal_passes().filter(|&p| !winning_squares(&self.board, p).is_empty()).collect()
+    }
+
+    /// true when every piece left to pass hands the other player a win
+    pub fn is_forced_loss(&self) -> bool {
+        self.legal_passes().all(|p| !winning_squares(&self.board, p).is_empty())
+    }
+
+    pub fn pass(self, p: Piece) -> Result<PlaceGame, MoveError> {
+        if self.board.contains(&p) {
+            Err(MoveError::PieceOnBoard)
         } else {
-            Some(PlaceGame{ board: self.board, passed: p, })
+            Ok(PlaceGame{ board: self.board, passed: p, player: self.player.switch() })
         }
     }
 }
@@ -129,89 +376,117 @@ impl PlaceGame {
     }
 
     pub fn passed(&self) -> Piece {
-        self.passed.clone()
+        self.passed
+    }
+
+    /// the player placing the passed piece
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    /// every empty square
+    pub fn legal_places(&self) -> impl Iterator<Item = (Idx, Idx)> {
+        let board = self.board;
+        ALL_SQUARES.iter().copied().filter(move |&(h, v)| board.get(h, v).is_none())
     }
 
-    pub fn place(self, square: (Idx, Idx)) -> Option<Either<FinalGame, PassGame>> {
+    /// every empty square where the passed piece wins now
+    pub fn winning_squares(&self) -> Vec<(Idx, Idx)> {
+        winning_squares(&self.board, self.passed)
+    }
+
+    pub fn place(self, square: (Idx, Idx)) -> Result<Either<FinalGame, PassGame>, MoveError> {
         self.board.place_piece(square, self.passed).map(|b|
             if has_win(&b) || b.is_full() {
-                Either::Left(FinalGame { board: b } )
+                Either::Left(FinalGame { board: b, player: self.player } )
             } else {
-                Either::Right(PassGame { board: b } )
+                // the player who places also passes next
+                Either::Right(PassGame { board: b, player: self.player } )
             }
         )
     }
 }
 
 pub fn new_game() -> PassGame {
-    PassGame { board: board::new_board() }
+    PassGame { board: board::new_board(), player: P1 }
 }
 
-fn row_has_win(row: &[Option<Piece>; 4]) -> bool {
-    fn r_has_win(r: &[Piece; 4]) -> bool {
-        let mut m = HashMap::new();
-
-        for p in r.iter() {
-            m.entry(C(p.color)).and_modify(|v| *v += 1).or_insert(1);
-            m.entry(H(p.height)).and_modify(|v| *v += 1).or_insert(1);
-            m.entry(S(p.shape)).and_modify(|v| *v += 1).or_insert(1);
-            m.entry(T(p.top)).and_modify(|v| *v += 1).or_insert(1);
-        };
+// builds a game from the parts of a position, rejecting any that could not come from play.
+// the board is expected to already be free of duplicate pieces.
+pub(crate) fn game_from_parts(board: Board, hand: Option<Piece>, is_final: bool, player: Player) -> Result<Game, PositionError> {
+    if let Some(p) = hand {
+        if board.contains(&p) {
+            return Err(PositionError::PieceInHandOnBoard(p));
+        }
+    }
 
-        m.iter().any(|(_, &x)| x == 4)
+    match (is_final, hand) {
+        (true, Some(_)) => Err(PositionError::UnexpectedPieceInHand),
+        (true, None) if !has_win(&board) && !board.is_full() => Err(PositionError::NotOver),
+        (true, None) if !wins_share_a_square(&board) => Err(PositionError::SeparateWins),
+        (false, _) if has_win(&board) => Err(PositionError::AlreadyWon),
+        (false, _) if board.is_full() => Err(PositionError::BoardFull),
+        _ if player != player_for(&board, hand) => Err(PositionError::WrongPlayer(player)),
+        (true, None) => Ok(FinalGame { board, player }.to_game()),
+        (false, None) => Ok(PassGame { board, player }.to_game()),
+        (false, Some(passed)) => Ok(PlaceGame { board, passed, player }.to_game()),
     }
+}
 
-    match (row[0], row[1], row[2], row[3]) {
-        (Some(a), Some(b), Some(c), Some(d)) => Some([a, b, c, d]),
-        _ => None,
-    }.iter().fold(false, |_, &r| r_has_win(&r))
+// the player to move, or for a final game the one who placed last. P1 makes the first
+// pass, so the parity of the pieces on the board says whose turn it is.
+fn player_for(board: &Board, hand: Option<Piece>) -> Player {
+    let passer = if board.piece_count().is_multiple_of(2) { P1 } else { P2 };
+    match hand {
+        Some(_) => passer.switch(),
+        // the last piece placed was placed by the player who would pass next
+        None => passer,
+    }
 }
 
-// rotates 90 deg clockwise
-fn rotate<T: Copy>(x: &[[T; 4]; 4]) -> [[T; 4]; 4] {
-    [
-        [x[3][0], x[2][0], x[1][0], x[0][0]],
-        [x[3][1], x[2][1], x[1][1], x[0][1]],
-        [x[3][2], x[2][2], x[1][2], x[0][2]],
-        [x[3][3], x[2][3], x[1][3], x[0][3]],
-    ]
+// play stops at the first win, so every winning line must include the last square placed
+fn wins_share_a_square(b: &Board) -> bool {
+    let wins = wins(b);
+    match wins.split_first() {
+        None => true,
+        Some((first, rest)) => first.line.squares().iter()
+            .any(|sq| rest.iter().all(|w| w.line.squares().contains(sq))),
+    }
 }
 
-fn has_win(b: &Board) -> bool {
-    let mut found_win = false;
-    let rows = b.raw();
-    let cols = rotate(&rows);
-    let diag1 = [
-        b.get(I1, I1),
-        b.get(I2, I2),
-        b.get(I3, I3),
-        b.get(I4, I4)
-    ];
-    let diag2 = [
-        b.get(I1, I4),
-        b.get(I2, I3),
-        b.get(I3, I2),
-        b.get(I4, I1)
-    ];
-    let win_lines: [[Option<Piece>; 4]; 10] = [
-        rows[0],
-        rows[1],
-        rows[2],
-        rows[3],
-        cols[0],
-        cols[1],
-        cols[2],
-        cols[3],
-        diag1,
-        diag2,
-    ];
-
-    for row in &win_lines {
-        if row_has_win(row) {
-            found_win = true;
-            break;
-        }
+// the attributes shared by all four pieces in a full line
+fn shared_attributes(line: &[Option<Piece>; 4]) -> Vec<Attribute> {
+    match (line[0], line[1], line[2], line[3]) {
+        (Some(a), Some(b), Some(c), Some(d)) => {
+            let rest = [b, c, d];
+            let mut shared = vec![];
+            if rest.iter().all(|p| p.color == a.color) { shared.push(C(a.color)); }
+            if rest.iter().all(|p| p.height == a.height) { shared.push(H(a.height)); }
+            if rest.iter().all(|p| p.shape == a.shape) { shared.push(S(a.shape)); }
+            if rest.iter().all(|p| p.top == a.top) { shared.push(T(a.top)); }
+            shared
+        },
+        _ => vec![],
     }
+}
 
-    found_win
-}
\ No newline at end of file
+fn wins(b: &Board) -> Vec<Win> {
+    ALL_LINES.iter()
+        .map(|&line| Win { line, attributes: shared_attributes(&b.line(line)) })
+        .filter(|w| !w.attributes.is_empty())
+        .collect()
+}
+
+// every empty square where `p` would complete a line
+fn winning_squares(b: &Board, p: Piece) -> Vec<(Idx, Idx)> {
+    let squares = b.winning_squares(p);
+    ALL_SQUARES.iter().copied()
+        .enumerate()
+        .filter(|&(i, _)| squares & 1 << i != 0)
+        .map(|(_, sq)| sq)
+        .collect()
+}
+
+fn has_win(b: &Board) -> bool {
+    b.has_win()
+}
@@ -16,33 +16,29 @@ use termion::{
 };
 
 
-#[derive(Copy, Clone)]
-#[derive(PartialEq, Eq, Hash)]
-#[derive(Debug)]
-pub enum Player {
-    P1,
-    P2,
-}
-
-impl Player {
-    fn switch(&self) -> Player {
-        match self {
-            Player::P1 => Player::P2,
-            Player::P2 => Player::P1,
-        }
-    }
-}
-
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
 pub struct State {
     game: Game,
-    player: Player,
     selection: Either<(bool, usize), (Idx, Idx)>,
     error: Option<&'static str>,
+    hints: bool,
+    // whether `solved` and `analysis` are up to date. they are only worked out while hints
+    // are shown, on the tick after the game changes, so the move itself is drawn first.
+    analyzed: bool,
+    // the result with perfect play from here, once few enough squares are empty
+    solved: Option<Outcome>,
+    // the engine's recommendation
+    analysis: Option<Analysis>,
 }
 
+// the most empty squares the "who wins" readout solves for
+static SOLVED_EMPTY_SQUARES: usize = 8;
+
+// recommends moves while hints are shown
+static HINT_ENGINE: AlphaBeta = AlphaBeta { depth: 32, node_limit: Some(200_000) };
+
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
@@ -50,6 +46,7 @@ pub enum Action {
     Quit,
     Submit,
     Move(Direction), 
+    Hint,
     Idle,
 }
 
@@ -63,6 +60,58 @@ pub enum Direction {
     Down,
 }
 
+impl State {
+    /// starts from any position with the cursor where the next move is made
+    pub fn from_game(game: Game) -> State {
+        let selection = match game {
+            Place(_) => Right((I1, I1)),
+            _ => Left((true, 0)),
+        };
+        State { game, selection, error: None, hints: false, analyzed: false, solved: None, analysis: None }
+    }
+
+    pub fn game(&self) -> Game {
+        self.game
+    }
+
+    /// the pass or place the cursor is currently on
+    pub fn selected_move(&self) -> Move {
+        match self.selection {
+            Left(cursor) => Move::Pass(ALL_PIECES[cursor.1 + if cursor.0 {0} else {8}]),
+            Right(square) => Move::Place(square.0, square.1),
+        }
+    }
+
+    pub fn play_move(self, m: Move) -> Result<State, MoveError> {
+        let new_cursor = match m {
+            Move::Pass(_) => Right((I1, I1)),
+            Move::Place(..) => Left((true, 0)),
+        };
+        self.game.apply(m).map(|g| State {
+            game: g,
+            selection: new_cursor,
+            error: self.error,
+            analyzed: false,
+            solved: None,
+            analysis: None,
+            ..self
+        })
+    }
+
+    /// shows or hides unsafe passes, winning squares, who wins with perfect play and the
+    /// engine's recommendation
+    pub fn toggle_hints(self) -> State {
+        State { hints: !self.hints, analyzed: false, solved: None, analysis: None, ..self }
+    }
+
+    // works out the readouts that are shown but not yet up to date
+    fn analyze(self) -> State {
+        if !self.hints || self.analyzed {
+            return self;
+        }
+        State { analyzed: true, solved: solve(self.game), analysis: HINT_ENGINE.search(&self.game), ..self }
+    }
+}
 
 pub struct LocalApp {}
 
@@ -72,12 +121,7 @@ impl App for LocalApp {
     type Output = State;
 
     fn initial_state() -> Self::State {
-        State {
-            game: quarto::new_game().to_game(),
-            player: Player::P1,
-            selection: Left((true, 0)),
-            error: None,
-        }
+        State::from_game(quarto::new_game().to_game())
     }
 
     fn write_state<W: io::Write>(f: &mut W, state: Self::State) {
@@ -89,15 +133,20 @@ impl App for LocalApp {
         let mut cursor: (u16, u16) = (8, 8);
 
         // write descriptor string
-        let player_str = match state.player {
-            Player::P1 => String::from("P1"),
-            Player::P2 => String::from("P2"),
+        let player = match state.game {
+            Final(g) => g.winner(),
+            g => g.player_to_move(),
+        };
+        let player_str = match player {
+            Some(P1) => String::from("P1"),
+            Some(P2) => String::from("P2"),
+            None => String::from(""),
         };
         let descriptor = match state.game {
-            g@Final(_) if g.is_tie() => String::from("    Tie Game!!!     "), //11
+            Final(g) if g.outcome() == Outcome::Draw => String::from("    Tie Game!!!     "), //11
             Final(_) => String::from("     ") + &(player_str + " Wins!!!     "), //10
             Pass(_)  => String::from(" ") + &(player_str + ", Pass a piece.  "), //17
-            Place(_)  => player_str + &(", Place the piece."), //20
+            Place(_)  => player_str + ", Place the piece.", //20
             //36
         };
         write_at(cursor, f, &descriptor);
@@ -105,6 +154,18 @@ impl App for LocalApp {
 
         // write game board out
         cursor.0 = 6;
+        let winning_squares: Vec<(Idx, Idx)> = match state.game {
+            Final(g) => g.wins().iter().flat_map(|w| w.line.squares().to_vec()).collect(),
+            _ => vec![],
+        };
+        let hint_squares = match state.game {
+            Place(g) if state.hints => g.winning_squares(),
+            _ => vec![],
+        };
+        let unsafe_pieces = match state.game {
+            Pass(g) if state.hints => g.unsafe_pieces(),
+            _ => vec![],
+        };
         let mut square = (I1, I1);
         for row in state.game.board().raw().iter() {
             f.write_fmt(format_args!("{}", termion::cursor::Goto(cursor.0, cursor.1))).unwrap();
@@ -116,8 +177,9 @@ impl App for LocalApp {
                         *p
                     };
                 f.write_fmt(format_args!("{}", "| ")).unwrap();
-                write_piece(f, &passed_or_placed, either::Right(square) == state.selection);
-                f.write_fmt(format_args!("{}", " ")).unwrap();
+                let highlighted = either::Right(square) == state.selection || winning_squares.contains(&square);
+                write_piece(f, &passed_or_placed, highlighted);
+                write_marker(f, hint_squares.contains(&square).then(|| ("*", color::AnsiValue::rgb(0, 5, 0))));
                 square.1 = next(square.1).unwrap_or(I1);
             };
             square.0 = next(square.0).unwrap_or(square.0);
@@ -134,7 +196,7 @@ impl App for LocalApp {
             let available_piece = if state.game.contains(p) { None } else { Some(*p) };
             let selected = (either::Left(piece_cursor) == state.selection) && !state.game.is_final();
             write_piece(f, &available_piece, selected);
-            f.write_fmt(format_args!(" ")).unwrap();
+            write_marker(f, unsafe_pieces.contains(p).then(|| ("!", color::AnsiValue::rgb(5, 0, 0))));
             piece_cursor.1 += 1;
         }
         cursor.1 += 1;
@@ -146,11 +208,39 @@ impl App for LocalApp {
             let available_piece = if state.game.contains(p) { None } else { Some(*p) };
             let selected = (either::Left(piece_cursor) == state.selection) && !state.game.is_final();
             write_piece(f, &available_piece, selected);
-            f.write_fmt(format_args!(" ")).unwrap();
+            write_marker(f, unsafe_pieces.contains(p).then(|| ("!", color::AnsiValue::rgb(5, 0, 0))));
             piece_cursor.1 += 1;
         }
         cursor.1 += 2;
 
+        // write who wins from here
+        cursor.0 = 8;
+        let solved = match state.solved {
+            Some(Outcome::Win(P1)) => Some("perfect play: P1 wins."),
+            Some(Outcome::Win(P2)) => Some("perfect play: P2 wins."),
+            Some(Outcome::Draw) => Some(" perfect play: draw."),
+            None => None,
+        };
+        if let Some(s) = solved {
+            write_at(cursor, f, s);
+            cursor.1 += 2;
+        }
+
+        // write the engine's recommendation
+        if let Some(analysis) = state.analysis {
+            let action = match analysis.best {
+                Move::Pass(p) => format!("pass {}", p),
+                Move::Place(h, v) => format!("place {}", Square::new(h, v)),
+            };
+            let evaluation = match analysis.score {
+                Score::Win(plies) => format!("wins in {} plies", plies),
+                Score::Loss(plies) => format!("loses in {} plies", plies),
+                Score::Draw => String::from("even"),
+            };
+            write_at(cursor, f, &format!("hint: {}, {}.", action, evaluation));
+            cursor.1 += 2;
+        }
+
         // write any error messages
         cursor.0 = 14;
         match state.error {
@@ -168,9 +258,8 @@ impl App for LocalApp {
 
         // write "any key to exit" on a final game
         cursor.0 = 8;
-        match state.game {
-            Final(_) => write_at(cursor, f, "press any key to exit."),
-            _        => {},
+        if let Final(_) = state.game {
+            write_at(cursor, f, "press any key to exit.")
         }
     }
 
@@ -179,6 +268,7 @@ impl App for LocalApp {
             Some(Ok(key)) => match key {
                 Key::Char('q')  => Action::Quit,
                 Key::Char('\n') => Action::Submit,
+                Key::Char('h')  => Action::Hint,
                 Key::Left       => Action::Move(Direction::Left),
                 Key::Right      => Action::Move(Direction::Right),
                 Key::Up         => Action::Move(Direction::Up),
@@ -194,53 +284,43 @@ impl App for LocalApp {
         match (action, state.game) {
             (Action::Quit, _) => None, // exits
             (_, Final(_)) => Some(state), // do nothing. exit controlled in event loop.
-            (Action::Idle, _) => Some(state), // do nothing
-            (Action::Submit, _) => {
-                let selection = match state.selection {
-                    Left(cursor) => Left(ALL_PIECES[cursor.1 + if cursor.0 {0} else {8}]),
-                    Right(square) => Right(square),
-                };
-                let new_cursor = match state.selection {
-                    Left(_) => Right((I1, I1)),
-                    Right(_) => Left((true, 0)),
-                };
-                match play(state.game, selection) {
-                    None => Some(State { game: state.game, player: state.player, selection: state.selection, error: Some("try again.") }),
-                    Some(g@Place(_)) => Some(State { game: g, player: state.player.switch(), selection: new_cursor, error: state.error }),
-                    Some(g) => Some(State { game: g, player: state.player, selection: new_cursor, error: state.error }),
-                }
+            (Action::Idle, _) => Some(state.analyze()),
+            (Action::Hint, _) => Some(state.toggle_hints()),
+            (Action::Submit, _) => match state.play_move(state.selected_move()) {
+                Err(e) => Some(State { error: Some(error_message(e)), ..state }),
+                Ok(next) => Some(next),
             },
             (Action::Move(Direction::Up), Pass(_)) => match state.selection {
-                Left(cursor) => Some(State { game: state.game, player: state.player, selection: Left((true, cursor.1)), error: None }),
-                Right(_) => Some(State { game: state.game, player: state.player, selection: Left((true, 0)), error: None }), 
+                Left(cursor) => Some(State { selection: Left((true, cursor.1)), error: None, ..state }),
+                Right(_) => Some(State { selection: Left((true, 0)), error: None, ..state }), 
             },
             (Action::Move(Direction::Down), Pass(_)) => match state.selection {
-                Left(cursor) => Some(State { game: state.game, player: state.player, selection: Left((false, cursor.1)), error: None }),
-                Right(_) => Some(State { game: state.game, player: state.player, selection: Left((true, 0)), error: None }), 
+                Left(cursor) => Some(State { selection: Left((false, cursor.1)), error: None, ..state }),
+                Right(_) => Some(State { selection: Left((true, 0)), error: None, ..state }), 
             },
             (Action::Move(Direction::Left), Pass(_)) => match state.selection {
-                Left(cursor) => Some(State { game: state.game, player: state.player, selection: Left((cursor.0, if cursor.1==0 {0} else {cursor.1-1})), error: None }),
-                Right(_) => Some(State { game: state.game, player: state.player, selection: Left((true, 0)), error: None }), 
+                Left(cursor) => Some(State { selection: Left((cursor.0, if cursor.1==0 {0} else {cursor.1-1})), error: None, ..state }),
+                Right(_) => Some(State { selection: Left((true, 0)), error: None, ..state }), 
             },
             (Action::Move(Direction::Right), Pass(_)) => match state.selection {
-                Left(cursor) => Some(State { game: state.game, player: state.player, selection: Left((cursor.0, min(7, cursor.1+1))), error: None }),
-                Right(_) => Some(State { game: state.game, player: state.player, selection: Left((true, 0)), error: None }), 
+                Left(cursor) => Some(State { selection: Left((cursor.0, min(7, cursor.1+1))), error: None, ..state }),
+                Right(_) => Some(State { selection: Left((true, 0)), error: None, ..state }), 
             },
             (Action::Move(Direction::Up), Place(_)) => match state.selection {
-                Left(_) => Some(State { game: state.game, player: state.player, selection: Right((I1, I1)), error: None }),
-                Right(square) => Some(State { game: state.game, player: state.player, selection: Right((prev(square.0).unwrap_or(square.0), square.1)), error: None }), 
+                Left(_) => Some(State { selection: Right((I1, I1)), error: None, ..state }),
+                Right(square) => Some(State { selection: Right((prev(square.0).unwrap_or(square.0), square.1)), error: None, ..state }), 
             },
             (Action::Move(Direction::Down), Place(_)) => match state.selection {
-                Left(_) => Some(State { game: state.game, player: state.player, selection: Right((I1, I1)), error: None }),
-                Right(square) => Some(State { game: state.game, player: state.player, selection: Right((next(square.0).unwrap_or(square.0), square.1)), error: None }), 
+                Left(_) => Some(State { selection: Right((I1, I1)), error: None, ..state }),
+                Right(square) => Some(State { selection: Right((next(square.0).unwrap_or(square.0), square.1)), error: None, ..state }), 
             },
             (Action::Move(Direction::Left), Place(_)) => match state.selection {
-                Left(_) => Some(State { game: state.game, player: state.player, selection: Right((I1, I1)), error: None }),
-                Right(square) => Some(State { game: state.game, player: state.player, selection: Right((square.0, prev(square.1).unwrap_or(square.1))), error: None }), 
+                Left(_) => Some(State { selection: Right((I1, I1)), error: None, ..state }),
+                Right(square) => Some(State { selection: Right((square.0, prev(square.1).unwrap_or(square.1))), error: None, ..state }), 
             },
             (Action::Move(Direction::Right), Place(_)) => match state.selection {
-                Left(_) => Some(State { game: state.game, player: state.player, selection: Right((I1, I1)), error: None }),
-                Right(square) => Some(State { game: state.game, player: state.player, selection: Right((square.0, next(square.1).unwrap_or(square.1))), error: None }), 
+                Left(_) => Some(State { selection: Right((I1, I1)), error: None, ..state }),
+                Right(square) => Some(State { selection: Right((square.0, next(square.1).unwrap_or(square.1))), error: None, ..state }), 
             },
         }
     }
@@ -264,12 +344,7 @@ impl App for DisplayWinnerApp {
     type Output = Void;
 
     fn initial_state() -> Self::State {
-        State {
-            game: quarto::new_game().to_game(),
-            player: Player::P1,
-            selection: Left((true, 0)),
-            error: None,
-        }
+        State::from_game(quarto::new_game().to_game())
     }
 
     fn write_state<W: io::Write>(f: &mut W, state: Self::State) {
@@ -277,10 +352,7 @@ impl App for DisplayWinnerApp {
     }
 
     fn action_from(key: Option<std::result::Result<termion::event::Key, std::io::Error>>) -> Self::Action {
-        match key {
-            Some(Ok(_)) => true,
-            _ => false,
-        }
+        matches!(key, Some(Ok(_)))
     }
     
     fn step(state: Self::State, action: Self::Action) -> Option<Self::State> {
@@ -311,8 +383,8 @@ fn write_piece<W: io::Write>(f: &mut W, op: &Option<Piece>, selected: bool) {
         Some(p) => {
             let mut s: String = String::from("");
             match p.top {
-                Top::Flat => s = s + " ",
-                Top::Hole => s = s + "○",
+                Top::Flat => s += " ",
+                Top::Hole => s += "○",
             };
             match p.shape {
                 Shape::Round => s = String::from("(") + &s + ")",
@@ -379,29 +451,38 @@ fn write_piece<W: io::Write>(f: &mut W, op: &Option<Piece>, selected: bool) {
     }
 }
 
-fn play(game:Game, selection: Either<Piece, (Idx, Idx)>) -> Option<Game> {
-    match (game, selection) {
-        (Final(_), _) => None,
-        (Pass(g), Left(p)) => g.pass(p).map(|x| x.to_game()),
-        (Pass(_), _) => None,
-        (Place(g), Right(square)) => g.place(square).map(|y|
-            merge(bimap(y, |x| x.to_game(), |x| x.to_game()))
-        ),
-        (Place(_), _) => None
-    }
+// None = the game is over or too many squares are empty to solve quickly
+fn solve(game: Game) -> Option<Outcome> {
+    let player = game.player_to_move()?;
+    Solver::new(SOLVED_EMPTY_SQUARES).solve(&game).map(|s| match s.value {
+        Value::Win => Outcome::Win(player),
+        Value::Loss => Outcome::Win(player.switch()),
+        Value::Draw => Outcome::Draw,
+    })
 }
 
-fn bimap<A, B, C, D>(z: Either<A, B>, f: fn(A) -> C, g: fn(B) -> D) -> Either<C, D> {
-    match z {
-        Left(x)  => Left(f(x)),
-        Right(x) => Right(g(x)),
+// a one character mark after a square or piece, or a space
+fn write_marker<W: io::Write>(f: &mut W, marker: Option<(&str, color::AnsiValue)>) {
+    match marker {
+        None => f.write_fmt(format_args!(" ")).unwrap(),
+        Some((mark, c)) => f.write_fmt(format_args!("{bold}{c}{mark}{reset_color}{reset_style}",
+            bold = style::Bold,
+            c = color::Fg(c),
+            mark = mark,
+            reset_color = color::Fg(color::Reset),
+            reset_style = style::Reset
+        )).unwrap(),
     }
 }
 
-fn merge<A>(z: Either<A, A>) -> A {
-    match z {
-        Left(x)  => x,
-        Right(x) => x,
+fn error_message(e: MoveError) -> &'static str {
+    match e {
+        MoveError::PieceOnBoard   => "already placed.",
+        MoveError::PieceInHand    => "already passed.",
+        MoveError::SquareOccupied => "square taken.",
+        MoveError::GameOver       => "game over.",
+        MoveError::ExpectedPass   => "pass first.",
+        MoveError::ExpectedPlace  => "place first.",
     }
 }
 
@@ -1,12 +1,15 @@
 mod common;
 use crate::common::*;
 mod splash;
+mod computer;
 mod local;
+mod network;
+mod settings;
 
 use structopt::StructOpt;
 use std::{
     io::{Write, stdout},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use termion::{
     input::TermRead,
@@ -18,12 +21,29 @@ static TICK_MS: Duration = Duration::from_millis(50);
 
 #[derive(StructOpt)]
 struct Cli {
-    // takes no arguments
+    /// port to listen on when hosting a local network game
+    #[structopt(long, default_value = "4000")]
+    port: u16,
+
+    /// address of the host when joining a local network game
+    #[structopt(long, default_value = "127.0.0.1:4000")]
+    address: String,
+
+    /// position to start a pass and play game from, e.g. "WTRF3/4/4/4 pass - P2"
+    #[structopt(long)]
+    position: Option<quarto::Game>,
+
+    /// seed for the computer opponent. defaults to the current time.
+    #[structopt(long)]
+    seed: Option<u64>,
+
+    /// how well the computer opponent plays: beginner, casual, strong or expert
+    #[structopt(long, default_value = "casual")]
+    difficulty: quarto::Difficulty,
 }
 
 fn main() {
-    // guards against passing arguments that won't be used.
-    let _args = Cli::from_args();
+    let args = Cli::from_args();
     
     {
         let mut stdin = termion::async_stdin().keys();
@@ -35,12 +55,45 @@ fn main() {
             termion::cursor::Goto(1, 1),
             termion::cursor::Hide).unwrap();
 
+        // run the splash screen, coming back to it from the settings screen
+        let mut difficulty = args.difficulty;
+        let mode = loop {
+            match splash::SplashApp::run(&mut stdout, &mut stdin, TICK_MS) {
+                Some(splash::Mode::Settings) => {
+                    // leaving without saving keeps the difficulty as it was
+                    if let Some(d) = settings::SettingsApp::run_from(settings::State::new(difficulty), &mut stdout, &mut stdin, TICK_MS) {
+                        difficulty = d;
+                    }
+                    write!(stdout, "{}", termion::clear::All).unwrap();
+                },
+                mode => break mode,
+            }
+        };
+
         let _run_app = 
-            // run the splash screen
-            splash::SplashApp::run(&mut stdout, &mut stdin, TICK_MS)
-                .and_then(|_| // TODO switch on mode here.
-                    local::LocalApp::run(&mut stdout, &mut stdin, TICK_MS)
-                ).and_then(|final_game|
+            mode
+                .and_then(|mode| match mode {
+                    splash::Mode::PassAndPlay => match args.position {
+                        Some(game) => local::LocalApp::run_from(local::State::from_game(game), &mut stdout, &mut stdin, TICK_MS),
+                        None => local::LocalApp::run(&mut stdout, &mut stdin, TICK_MS),
+                    },
+                    splash::Mode::Computer => {
+                        let seed = args.seed.unwrap_or_else(|| 
+                            SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+                        );
+                        computer::OpponentApp::run_from(computer::MenuState::new(difficulty), &mut stdout, &mut stdin, TICK_MS)
+                            .and_then(|opponent|
+                                computer::ComputerApp::run_from(computer::State::new(opponent, seed), &mut stdout, &mut stdin, TICK_MS)
+                            )
+                    },
+                    splash::Mode::LocalNetwork =>
+                        network::ConnectApp::run(&mut stdout, &mut stdin, TICK_MS)
+                            .and_then(|role|
+                                network::run(role, args.port, &args.address, &mut stdout, &mut stdin, TICK_MS)
+                            ),
+                    // the loop above only stops on the other modes
+                    splash::Mode::Settings => None,
+                }).and_then(|final_game|
                     // game is over. wait for user to quit.
                     local::DisplayWinnerApp::run_from(final_game, &mut stdout, &mut stdin, TICK_MS)
                 );
@@ -52,5 +105,5 @@ fn main() {
             termion::cursor::Goto(1, 1)
         ).unwrap();
     }
-    println!("{}", "Done.")
+    println!("Done.")
 }
@@ -0,0 +1,165 @@
+use crate::{Game, Move, PassGame, PlaceGame, Player, Final};
+use crate::agent::{chosen_pass, chosen_place, Agent};
+use crate::board::Idx;
+use crate::piece::Piece;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use std::{
+    cmp::Reverse,
+    time::{Duration, Instant},
+};
+
+// how strongly selection favors moves that have been tried less
+const EXPLORATION: f64 = 1.4;
+
+/// Monte Carlo tree search with random playouts. The same seed always gives the same
+/// results, unless `time_limit` stops the search before `iterations` is reached.
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct Mcts {
+    pub iterations: u64,
+    /// None = no limit
+    pub time_limit: Option<Duration>,
+    pub seed: u64,
+}
+
+/// How one move from the searched position did, for the player making it.
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct Candidate {
+    pub action: Move,
+    pub visits: u64,
+    pub wins: u64,
+    pub draws: u64,
+}
+
+impl Candidate {
+    /// draws count as half a win. 0 for a move that was never visited.
+    pub fn win_rate(&self) -> f64 {
+        if self.visits == 0 {
+            0.0
+        } else {
+            (self.wins as f64 + self.draws as f64 / 2.0) / self.visits as f64
+        }
+    }
+}
+
+impl Default for Mcts {
+    fn default() -> Mcts {
+        Mcts { iterations: 10_000, time_limit: None, seed: 0 }
+    }
+}
+
+impl Mcts {
+    /// Every legal move with the most visited first. Empty once the game is over.
+    pub fn search(&self, game: &Game) -> Vec<Candidate> {
+        if game.is_final() {
+            return vec![];
+        }
+        let start = Instant::now();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut tree = vec![Node::new(*game, None, None)];
+
+        for _ in 0..self.iterations {
+            if self.time_limit.is_some_and(|t| start.elapsed() >= t) {
+                break;
+            }
+
+            // select a path down to a node with an untried move, or to a final game
+            let mut path = vec![0];
+            let mut current = 0;
+            while tree[current].untried.is_empty() && !tree[current].children.is_empty() {
+                current = best_child(&tree, current);
+                path.push(current);
+            }
+
+            // expand one untried move
+            if let Some(m) = tree[current].untried.pop() {
+                let parent = tree[current].game;
+                // untried moves come from the game's own legal moves
+                let child = parent.apply(m).unwrap();
+                tree.push(Node::new(child, Some(m), parent.player_to_move()));
+                let index = tree.len() - 1;
+                tree[current].children.push(index);
+                path.push(index);
+                current = index;
+            }
+
+            let outcome = playout(tree[current].game, &mut rng);
+            for &i in &path {
+                let node = &mut tree[i];
+                node.visits += 1;
+                match (outcome, node.mover) {
+                    (Some(winner), Some(mover)) if winner == mover => node.wins += 1,
+                    (None, _) => node.draws += 1,
+                    _ => {},
+                }
+            }
+        }
+
+        let mut candidates: Vec<Candidate> = tree[0].children.iter()
+            .map(|&i| &tree[i])
+            .map(|n| Candidate { action: n.action.unwrap(), visits: n.visits, wins: n.wins, draws: n.draws })
+            .chain(tree[0].untried.iter().map(|&m| Candidate { action: m, visits: 0, wins: 0, draws: 0 }))
+            .collect();
+        candidates.sort_by_key(|c| Reverse(c.visits));
+        candidates
+    }
+}
+
+impl Agent for Mcts {
+    fn choose_pass(&mut self, game: &PassGame) -> Piece {
+        chosen_pass(game, self.search(&game.to_game()).first().map(|c| c.action))
+    }
+
+    fn choose_place(&mut self, game: &PlaceGame) -> (Idx, Idx) {
+        chosen_place(game, self.search(&game.to_game()).first().map(|c| c.action))
+    }
+}
+
+struct Node {
+    game: Game,
+    // the move that led here. None for the root.
+    action: Option<Move>,
+    // the player who made that move
+    mover: Option<Player>,
+    children: Vec<usize>,
+    untried: Vec<Move>,
+    visits: u64,
+    wins: u64,
+    draws: u64,
+}
+
+impl Node {
+    fn new(game: Game, action: Option<Move>, mover: Option<Player>) -> Node {
+        Node { game, action, mover, children: vec![], untried: game.legal_moves(), visits: 0, wins: 0, draws: 0 }
+    }
+}
+
+// the child with the highest upper confidence bound
+fn best_child(tree: &[Node], parent: usize) -> usize {
+    let log_visits = (tree[parent].visits as f64).ln();
+    let bound = |i: usize| {
+        let n = &tree[i];
+        let visits = n.visits as f64;
+        (n.wins as f64 + n.draws as f64 / 2.0) / visits + EXPLORATION * (log_visits / visits).sqrt()
+    };
+    // only called once every child has been visited
+    *tree[parent].children.iter()
+        .max_by(|&&a, &&b| bound(a).partial_cmp(&bound(b)).unwrap())
+        .unwrap()
+}
+
+// plays random moves to the end of the game. None = a draw.
+fn playout(game: Game, rng: &mut StdRng) -> Option<Player> {
+    let mut game = game;
+    loop {
+        if let Final(g) = game {
+            return g.winner();
+        }
+        // only a final game has no legal moves
+        let m = *game.legal_moves().choose(rng).unwrap();
+        game = game.apply(m).unwrap();
+    }
+}
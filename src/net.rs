@@ -0,0 +1,141 @@
+use crate::Move;
+use crate::board::Idx;
+use crate::piece::Piece;
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+};
+
+// moves are sent one per line:
+//   "pass <n>"      where n is the piece's code
+//   "place <r> <c>" where r and c are the square's row and column indices
+
+pub struct Host {
+    listener: TcpListener,
+}
+
+impl Host {
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Host> {
+        TcpListener::bind(addr).map(|listener| Host { listener })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// blocks until an opponent joins
+    pub fn accept(&self) -> io::Result<Connection> {
+        self.listener.accept().and_then(|(stream, _)| Connection::from_stream(stream))
+    }
+
+    /// None = no opponent has joined yet
+    pub fn try_accept(&self) -> io::Result<Option<Connection>> {
+        self.listener.set_nonblocking(true)?;
+        let accepted = self.listener.accept();
+        self.listener.set_nonblocking(false)?;
+
+        match accepted {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+            Ok((stream, _)) => Connection::from_stream(stream).map(Some),
+        }
+    }
+}
+
+pub struct Connection {
+    stream: TcpStream,
+    buf: Vec<u8>,
+}
+
+impl Connection {
+    pub fn join<A: ToSocketAddrs>(addr: A) -> io::Result<Connection> {
+        TcpStream::connect(addr).and_then(Connection::from_stream)
+    }
+
+    fn from_stream(stream: TcpStream) -> io::Result<Connection> {
+        // accepted streams may inherit non-blocking mode from the listener
+        stream.set_nonblocking(false)?;
+        stream.set_nodelay(true)?;
+        Ok(Connection { stream, buf: Vec::new() })
+    }
+
+    pub fn send(&mut self, m: Move) -> io::Result<()> {
+        let line = match m {
+            Move::Pass(p) => format!("pass {}\n", p.to_bits()),
+            Move::Place(h, v) => format!("place {} {}\n", h.to_i(), v.to_i()),
+        };
+        self.stream.write_all(line.as_bytes())?;
+        self.stream.flush()
+    }
+
+    /// blocks until the opponent's next move arrives
+    pub fn recv(&mut self) -> io::Result<Move> {
+        loop {
+            if let Some(m) = self.take_move()? {
+                return Ok(m);
+            }
+            self.fill()?;
+        }
+    }
+
+    /// None = the opponent has not sent a move yet
+    pub fn try_recv(&mut self) -> io::Result<Option<Move>> {
+        if let Some(m) = self.take_move()? {
+            return Ok(Some(m));
+        }
+
+        self.stream.set_nonblocking(true)?;
+        let filled = self.fill();
+        self.stream.set_nonblocking(false)?;
+
+        match filled {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+            Ok(()) => self.take_move(),
+        }
+    }
+
+    fn fill(&mut self) -> io::Result<()> {
+        let mut chunk = [0; 64];
+        match self.stream.read(&mut chunk)? {
+            0 => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "opponent disconnected")),
+            n => {
+                self.buf.extend_from_slice(&chunk[..n]);
+                Ok(())
+            },
+        }
+    }
+
+    fn take_move(&mut self) -> io::Result<Option<Move>> {
+        match self.buf.iter().position(|&b| b == b'\n') {
+            None => Ok(None),
+            Some(end) => {
+                let line: Vec<u8> = self.buf.drain(..=end).collect();
+                let parsed = std::str::from_utf8(&line[..end]).ok().and_then(parse_move);
+                match parsed {
+                    Some(m) => Ok(Some(m)),
+                    None => Err(io::Error::new(io::ErrorKind::InvalidData, "malformed move")),
+                }
+            },
+        }
+    }
+}
+
+fn parse_move(line: &str) -> Option<Move> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["pass", n] => n.parse::<u8>().ok()
+            .and_then(|bits| Piece::try_from(bits).ok())
+            .map(Move::Pass),
+        ["place", r, c] => match (parse_idx(r), parse_idx(c)) {
+            (Some(r), Some(c)) => Some(Move::Place(r, c)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn parse_idx(s: &str) -> Option<Idx> {
+    s.parse::<usize>().ok().and_then(Idx::from_i)
+}
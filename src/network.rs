@@ -0,0 +1,244 @@
+use crate::common::*;
+use crate::local::{LocalApp, State as GameState};
+use quarto::{Connection, Host, Player};
+use std::{
+    cmp::min,
+    io,
+    thread,
+    time::Duration,
+};
+use termion::{
+    color,
+    event::Key,
+};
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct State {
+    // index into MENU
+    cursor: usize,
+    role: Option<Role>,
+}
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum Action {
+    CursorUp,
+    CursorDown,
+    Submit,
+    Quit,
+    Idle,
+}
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum Role {
+    Host,
+    Join,
+}
+
+impl Role {
+    // the host always passes first
+    fn player(&self) -> Player {
+        match self {
+            Role::Host => Player::P1,
+            Role::Join => Player::P2,
+        }
+    }
+}
+
+static MENU: [(Role, &str); 2] = [
+    (Role::Host, "           Host Game           "),
+    (Role::Join, "           Join Game           "),
+];
+
+pub struct ConnectApp {}
+
+impl App for ConnectApp {
+    type State = State;
+    type Action = Action;
+    type Output = Role;
+
+    fn initial_state() -> Self::State {
+        State { cursor: 0, role: None }
+    }
+
+    fn write_state<W: io::Write>(f: &mut W, state: Self::State) {
+        let mut cursor: (u16, u16) = (1, 2);
+
+        write_banner_at(cursor, f);
+        cursor = (3, 8);
+
+        for (i, (_, label)) in MENU.iter().enumerate() {
+            cursor.1 += 1;
+            if state.cursor == i {
+                f.write_fmt(format_args!("{}{}{}{}",
+                    termion::cursor::Goto(cursor.0, cursor.1),
+                    color::Bg(color::AnsiValue::grayscale(12)),
+                    label,
+                    color::Bg(color::Reset)
+                )).unwrap();
+            } else {
+                write_at(cursor, f, label);
+            }
+        }
+
+        cursor.1 += 2;
+        write_at(cursor, f, "         - q to quit -         ");
+    }
+
+    fn action_from(key: Option<std::result::Result<termion::event::Key, std::io::Error>>) -> Self::Action {
+        match key {
+            Some(Ok(key)) => match key {
+                Key::Char('q')  => Action::Quit,
+                Key::Char('\n') => Action::Submit,
+                Key::Up         => Action::CursorUp,
+                Key::Down       => Action::CursorDown,
+                _               => Action::Idle,
+            },
+            // throws errors away
+            _ => Action::Idle,
+        }
+    }
+
+    fn step(state: Self::State, action: Self::Action) -> Option<Self::State> {
+        match action {
+            Action::Quit => None,
+            Action::Idle => Some(state),
+            Action::Submit => Some(State { cursor: state.cursor, role: Some(MENU[state.cursor].0) }),
+            Action::CursorUp => Some(State { cursor: state.cursor.saturating_sub(1), role: state.role }),
+            Action::CursorDown => Some(State { cursor: min(MENU.len() - 1, state.cursor + 1), role: state.role }),
+        }
+    }
+
+    fn output_from(state: Self::State) -> Option<Self::Output> {
+        state.role
+    }
+}
+
+/// Plays a game against a remote opponent using the `LocalApp` board and controls.
+/// Only accepts moves from the keyboard on this player's turn.
+/// None = exit; Some holds the final state of the game
+pub fn run<W: io::Write>(
+    role: Role,
+    port: u16,
+    address: &str,
+    f: &mut W,
+    input: &mut termion::input::Keys<termion::AsyncReader>,
+    tick_ms: Duration
+) -> Option<GameState> {
+    let mut conn = match role {
+        Role::Host => host(port, f, input, tick_ms)?,
+        Role::Join => match Connection::join(address) {
+            Ok(conn) => conn,
+            Err(_) => return show_error(f, input, tick_ms, "could not connect to host."),
+        },
+    };
+
+    let me = role.player();
+    let mut state = LocalApp::initial_state();
+    let mut status: Option<&'static str> = None;
+
+    while LocalApp::output_from(state).is_none() {
+        LocalApp::write_state(f, state);
+        write_status(f, state, me, status);
+        f.flush().unwrap();
+
+        let action = LocalApp::action_from(input.next());
+        if action == crate::local::Action::Quit {
+            return None;
+        }
+
+        if status.is_none() {
+            if state.game().player_to_move() == Some(me) {
+                let selected = state.selected_move();
+                let next = LocalApp::step(state, action)?;
+                if next.game() != state.game() && conn.send(selected).is_err() {
+                    status = Some("lost connection to opponent.");
+                }
+                state = next;
+            } else {
+                match conn.try_recv() {
+                    Ok(None) => {},
+                    Ok(Some(m)) => match state.play_move(m) {
+                        Ok(next) => state = next,
+                        Err(_) => status = Some("opponent sent an illegal move."),
+                    },
+                    Err(_) => status = Some("lost connection to opponent."),
+                }
+            }
+        }
+        thread::sleep(tick_ms);
+    }
+
+    Some(state)
+}
+
+fn host<W: io::Write>(
+    port: u16,
+    f: &mut W,
+    input: &mut termion::input::Keys<termion::AsyncReader>,
+    tick_ms: Duration
+) -> Option<Connection> {
+    let host = match Host::bind(("0.0.0.0", port)) {
+        Ok(host) => host,
+        Err(_) => return show_error(f, input, tick_ms, "could not host on that port."),
+    };
+
+    f.write_fmt(format_args!("{}", termion::clear::All)).unwrap();
+    write_banner_at((1, 2), f);
+    write_at((3, 9), f, &format!("waiting for an opponent on port {}", port));
+    write_at((3, 11), f, "         - q to quit -         ");
+    f.flush().unwrap();
+
+    loop {
+        if let Some(Ok(Key::Char('q'))) = input.next() {
+            return None;
+        }
+        match host.try_accept() {
+            Ok(None) => thread::sleep(tick_ms),
+            Ok(Some(conn)) => return Some(conn),
+            Err(_) => return show_error(f, input, tick_ms, "could not accept an opponent."),
+        }
+    }
+}
+
+fn write_status<W: io::Write>(f: &mut W, state: GameState, me: Player, status: Option<&'static str>) {
+    let cursor = (8, 22);
+    match status {
+        Some(e) => f.write_fmt(format_args!("{pos}{red}{err}{reset}",
+            pos = termion::cursor::Goto(cursor.0, cursor.1),
+            red = color::Fg(color::Red),
+            err = e,
+            reset = color::Fg(color::Reset)
+        )).unwrap(),
+        None if state.game().player_to_move() != Some(me) => write_at(cursor, f, "waiting for opponent..."),
+        None => {},
+    }
+}
+
+// waits for any key so the error can be read before exiting
+fn show_error<W: io::Write, T>(
+    f: &mut W,
+    input: &mut termion::input::Keys<termion::AsyncReader>,
+    tick_ms: Duration,
+    e: &'static str
+) -> Option<T> {
+    f.write_fmt(format_args!("{clear}{pos}{red}{err}{reset}",
+        clear = termion::clear::All,
+        pos = termion::cursor::Goto(3, 9),
+        red = color::Fg(color::Red),
+        err = e,
+        reset = color::Fg(color::Reset)
+    )).unwrap();
+    write_at((3, 11), f, "press any key to exit.");
+    f.flush().unwrap();
+
+    while input.next().is_none() {
+        thread::sleep(tick_ms);
+    }
+    None
+}
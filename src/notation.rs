@@ -0,0 +1,148 @@
+use crate::{Game, Pass, Place, Final, Player, P1, P2, game_from_parts};
+use crate::board::{new_board, Board, Idx, ALL_SQUARES};
+use crate::error::{ParseGameError, PositionError};
+use crate::piece::Piece;
+use std::{fmt, str::FromStr};
+
+// A one line notation for a position, similar to chess's FEN:
+//
+//   <board> <phase> <piece in hand> <player>
+//
+// The board is written row by row from the top, with rows separated by '/'. Pieces are
+// written in their four-letter notation and each run of empty squares as a single count
+// from 1 to 4. The phase is one of pass, place or final, and the piece in hand is '-'
+// unless a piece is being placed. The player is the one to move, or for a final game the
+// one who moved last, and must fit the number of pieces on the board since P1 always
+// passes first. Parsing is case-insensitive.
+//
+//   new game:            4/4/4/4 pass - P1
+//   WTRF passed to P2:   4/4/4/4 place WTRF P2
+//   WTRF placed in a1:   WTRF3/4/4/4 pass - P2
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (phase, player) = match self {
+            Pass(g)  => ("pass", g.player()),
+            Place(g) => ("place", g.player()),
+            Final(g) => ("final", g.last_player()),
+        };
+        let hand = match self.get_passed_piece() {
+            Some(p) => p.to_string(),
+            None => String::from("-"),
+        };
+        write!(f, "{} {} {} {}", board_notation(self.board()), phase, hand, player_notation(player))
+    }
+}
+
+impl FromStr for Game {
+    type Err = ParseGameError;
+
+    fn from_str(s: &str) -> Result<Game, ParseGameError> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        if fields.len() != 4 {
+            return Err(ParseGameError::Fields(fields.len()));
+        }
+
+        let board = parse_board(fields[0])?;
+        let phase = fields[1].to_ascii_lowercase();
+        let is_final = match phase.as_str() {
+            "pass" | "place" => false,
+            "final" => true,
+            _ => return Err(ParseGameError::Phase),
+        };
+        let hand = match fields[2] {
+            "-" => None,
+            p => Some(p.parse::<Piece>()?),
+        };
+        let player = match fields[3].to_ascii_uppercase().as_str() {
+            "P1" => P1,
+            "P2" => P2,
+            _ => return Err(ParseGameError::Player),
+        };
+
+        match (phase.as_str(), hand) {
+            ("place", None) => Err(PositionError::MissingPieceInHand.into()),
+            ("pass", Some(_)) => Err(PositionError::UnexpectedPieceInHand.into()),
+            _ => Ok(game_from_parts(board, hand, is_final, player)?),
+        }
+    }
+}
+
+fn player_notation(p: Player) -> &'static str {
+    match p {
+        P1 => "P1",
+        P2 => "P2",
+    }
+}
+
+fn board_notation(b: &Board) -> String {
+    let rows: Vec<String> = b.raw().iter().map(|row| {
+        let mut s = String::new();
+        let mut empty = 0;
+        for square in row {
+            match square {
+                None => empty += 1,
+                Some(p) => {
+                    if empty > 0 {
+                        s += &empty.to_string();
+                        empty = 0;
+                    }
+                    s += &p.to_string();
+                },
+            }
+        }
+        if empty > 0 {
+            s += &empty.to_string();
+        }
+        s
+    }).collect();
+    rows.join("/")
+}
+
+fn parse_board(s: &str) -> Result<Board, ParseGameError> {
+    let rows: Vec<&str> = s.split('/').collect();
+    if rows.len() != 4 {
+        return Err(ParseGameError::Rows(rows.len()));
+    }
+
+    let mut board = new_board();
+    for (r, row) in rows.iter().enumerate() {
+        let chars: Vec<char> = row.chars().collect();
+        let mut col = 0;
+        let mut i = 0;
+        while i < chars.len() {
+            if let Some(empty) = chars[i].to_digit(10) {
+                // a run of empty squares is one digit, so each position has one notation
+                let follows_run = i > 0 && chars[i - 1].is_ascii_digit();
+                if empty == 0 || follows_run {
+                    return Err(ParseGameError::Row(r));
+                }
+                col += empty as usize;
+                i += 1;
+            } else {
+                let end = i + 4;
+                if end > chars.len() || col >= 4 {
+                    return Err(ParseGameError::Row(r));
+                }
+                let p = chars[i..end].iter().collect::<String>().parse::<Piece>()?;
+                if board.contains(&p) {
+                    return Err(PositionError::DuplicatePiece(p).into());
+                }
+                board = board.place_piece(square(r, col), p).unwrap();
+                col += 1;
+                i = end;
+            }
+            if col > 4 {
+                return Err(ParseGameError::Row(r));
+            }
+        }
+        if col != 4 {
+            return Err(ParseGameError::Row(r));
+        }
+    }
+    Ok(board)
+}
+
+fn square(row: usize, col: usize) -> (Idx, Idx) {
+    ALL_SQUARES[row * 4 + col]
+}
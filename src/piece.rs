@@ -1,4 +1,5 @@
-use std::fmt;
+use crate::error::{InvalidPieceCode, ParsePieceError};
+use std::{convert::TryFrom, fmt, str::FromStr};
 
 pub use Color::*;
 pub use Height::*;
@@ -8,6 +9,7 @@ pub use Top::*;
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     White,
     Black,
@@ -16,6 +18,7 @@ pub enum Color {
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Height {
     Tall,
     Short,
@@ -24,6 +27,7 @@ pub enum Height {
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Shape {
     Round,
     Square,
@@ -32,6 +36,7 @@ pub enum Shape {
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Top {
     Flat,
     Hole,
@@ -40,6 +45,7 @@ pub enum Top {
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Piece {
     pub color: Color,
     pub height: Height,
@@ -47,30 +53,149 @@ pub struct Piece {
     pub top: Top,
 }
 
+// A piece's 4-bit code has one bit per attribute, ordered so the code is the piece's
+// index in ALL_PIECES: color (bit 3), height (bit 2), shape (bit 1), top (bit 0).
+
+impl Color {
+    pub fn to_bits(self) -> u8 {
+        match self { White => 0, Black => 0b1000 }
+    }
+
+    /// reads bit 3 of a piece code
+    pub fn from_bits(bits: u8) -> Color {
+        if bits & 0b1000 == 0 { White } else { Black }
+    }
+}
+
+impl Height {
+    pub fn to_bits(self) -> u8 {
+        match self { Tall => 0, Short => 0b0100 }
+    }
+
+    /// reads bit 2 of a piece code
+    pub fn from_bits(bits: u8) -> Height {
+        if bits & 0b0100 == 0 { Tall } else { Short }
+    }
+}
+
+impl Shape {
+    pub fn to_bits(self) -> u8 {
+        match self { Round => 0, Square => 0b0010 }
+    }
+
+    /// reads bit 1 of a piece code
+    pub fn from_bits(bits: u8) -> Shape {
+        if bits & 0b0010 == 0 { Round } else { Square }
+    }
+}
+
+impl Top {
+    pub fn to_bits(self) -> u8 {
+        match self { Flat => 0, Hole => 0b0001 }
+    }
+
+    /// reads bit 0 of a piece code
+    pub fn from_bits(bits: u8) -> Top {
+        if bits & 0b0001 == 0 { Flat } else { Hole }
+    }
+}
+
+impl Piece {
+    pub fn to_bits(self) -> u8 {
+        self.color.to_bits() | self.height.to_bits() | self.shape.to_bits() | self.top.to_bits()
+    }
+
+    /// only the low four bits are used. see `TryFrom<u8>` to reject larger values.
+    pub fn from_bits(bits: u8) -> Piece {
+        Piece {
+            color: Color::from_bits(bits),
+            height: Height::from_bits(bits),
+            shape: Shape::from_bits(bits),
+            top: Top::from_bits(bits),
+        }
+    }
+}
+
+impl From<Piece> for u8 {
+    fn from(p: Piece) -> u8 {
+        p.to_bits()
+    }
+}
+
+impl TryFrom<u8> for Piece {
+    type Error = InvalidPieceCode;
+
+    fn try_from(bits: u8) -> Result<Piece, InvalidPieceCode> {
+        if bits < 16 {
+            Ok(Piece::from_bits(bits))
+        } else {
+            Err(InvalidPieceCode(bits))
+        }
+    }
+}
+
 impl fmt::Display for Piece {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut s: String = String::from("");
         match self.color {
-            Color::White => s = s + "W",
-            Color::Black => s = s + "B",
+            Color::White => s += "W",
+            Color::Black => s += "B",
         }
         match self.height {
-            Height::Tall => s = s + "T",
-            Height::Short => s = s + "S",
+            Height::Tall => s += "T",
+            Height::Short => s += "S",
         }
         match self.shape {
-            Shape::Round => s = s + "R",
-            Shape::Square => s = s + "Q",
+            Shape::Round => s += "R",
+            Shape::Square => s += "Q",
         }
         match self.top {
-            Top::Flat => s = s + "F",
-            Top::Hole => s = s + "H",
+            Top::Flat => s += "F",
+            Top::Hole => s += "H",
         }
         write!(f, "{}", s)
     }
 }
 
+// the inverse of Display. case-insensitive: "wtrf" parses as WTRF.
+impl FromStr for Piece {
+    type Err = ParsePieceError;
+
+    fn from_str(s: &str) -> Result<Piece, ParsePieceError> {
+        let letters: Vec<char> = s.chars().map(|c| c.to_ascii_uppercase()).collect();
+        if letters.len() != 4 {
+            return Err(ParsePieceError::Length(letters.len()));
+        }
+        let bad_letter = |index: usize| ParsePieceError::Letter { index, found: s.chars().nth(index).unwrap() };
+
+        let color = match letters[0] {
+            'W' => White,
+            'B' => Black,
+            _   => return Err(bad_letter(0)),
+        };
+        let height = match letters[1] {
+            'T' => Tall,
+            'S' => Short,
+            _   => return Err(bad_letter(1)),
+        };
+        let shape = match letters[2] {
+            'R' => Round,
+            'Q' => Square,
+            _   => return Err(bad_letter(2)),
+        };
+        let top = match letters[3] {
+            'F' => Flat,
+            'H' => Hole,
+            _   => return Err(bad_letter(3)),
+        };
+        Ok(Piece { color, height, shape, top })
+    }
+}
+
+#[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Attribute {
     C(Color),
     H(Height),
@@ -0,0 +1,81 @@
+use crate::{Game, Move};
+use crate::error::MoveError;
+
+/// A game along with every pass and place that led to it.
+/// Undone moves are kept for redo until a different move is played.
+#[derive(Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "crate::serialize::RawGameRecord", try_from = "crate::serialize::RawGameRecord"))]
+pub struct GameRecord {
+    moves: Vec<Move>,
+    // states[i] is the game after the first i moves
+    states: Vec<Game>,
+    ply: usize,
+}
+
+impl GameRecord {
+    /// the game at the current ply
+    pub fn game(&self) -> Game {
+        self.states[self.ply]
+    }
+
+    /// the number of moves played to reach the current game
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// every recorded move, including any that have been undone
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// the moves played to reach the current game
+    pub fn history(&self) -> &[Move] {
+        &self.moves[..self.ply]
+    }
+
+    /// Discards any undone moves.
+    pub fn play(&mut self, m: Move) -> Result<Game, MoveError> {
+        let g = self.game().apply(m)?;
+        self.moves.truncate(self.ply);
+        self.states.truncate(self.ply + 1);
+        self.moves.push(m);
+        self.states.push(g);
+        self.ply += 1;
+        Ok(g)
+    }
+
+    /// None = there are no moves to undo
+    pub fn undo(&mut self) -> Option<Game> {
+        if self.ply == 0 {
+            None
+        } else {
+            self.jump_to(self.ply - 1)
+        }
+    }
+
+    /// None = there are no undone moves to redo
+    pub fn redo(&mut self) -> Option<Game> {
+        self.jump_to(self.ply + 1)
+    }
+
+    /// None = fewer than `ply` moves have been recorded
+    pub fn jump_to(&mut self, ply: usize) -> Option<Game> {
+        if ply < self.states.len() {
+            self.ply = ply;
+            Some(self.game())
+        } else {
+            None
+        }
+    }
+}
+
+pub fn new_record() -> GameRecord {
+    GameRecord {
+        moves: vec![],
+        states: vec![crate::new_game().to_game()],
+        ply: 0,
+    }
+}
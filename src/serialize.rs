@@ -0,0 +1,85 @@
+// Serialized games are checked the same way parsed positions are, so deserializing
+// can never produce a game that could not have been reached by play.
+use crate::{Board, Game, Move, Piece, Player, PassGame, PlaceGame, FinalGame, game_from_parts};
+use crate::error::{PositionError, ReplayError};
+use crate::record::{new_record, GameRecord};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+#[derive(Deserialize)]
+pub struct RawPassGame {
+    board: Board,
+    player: Player,
+}
+
+#[derive(Deserialize)]
+pub struct RawPlaceGame {
+    board: Board,
+    passed: Piece,
+    player: Player,
+}
+
+#[derive(Deserialize)]
+pub struct RawFinalGame {
+    board: Board,
+    player: Player,
+}
+
+// only the moves are kept. the states are rebuilt by replaying them.
+#[derive(Serialize, Deserialize)]
+pub struct RawGameRecord {
+    moves: Vec<Move>,
+    ply: usize,
+}
+
+impl TryFrom<RawPassGame> for PassGame {
+    type Error = PositionError;
+
+    fn try_from(raw: RawPassGame) -> Result<PassGame, PositionError> {
+        match game_from_parts(raw.board, None, false, raw.player)? {
+            Game::Pass(g) => Ok(g),
+            _ => unreachable!("a game that is not final without a piece in hand is a pass"),
+        }
+    }
+}
+
+impl TryFrom<RawPlaceGame> for PlaceGame {
+    type Error = PositionError;
+
+    fn try_from(raw: RawPlaceGame) -> Result<PlaceGame, PositionError> {
+        match game_from_parts(raw.board, Some(raw.passed), false, raw.player)? {
+            Game::Place(g) => Ok(g),
+            _ => unreachable!("a game that is not final with a piece in hand is a place"),
+        }
+    }
+}
+
+impl TryFrom<RawFinalGame> for FinalGame {
+    type Error = PositionError;
+
+    fn try_from(raw: RawFinalGame) -> Result<FinalGame, PositionError> {
+        match game_from_parts(raw.board, None, true, raw.player)? {
+            Game::Final(g) => Ok(g),
+            _ => unreachable!("a final game is always final"),
+        }
+    }
+}
+
+impl From<GameRecord> for RawGameRecord {
+    fn from(record: GameRecord) -> RawGameRecord {
+        RawGameRecord { moves: record.moves().to_vec(), ply: record.ply() }
+    }
+}
+
+impl TryFrom<RawGameRecord> for GameRecord {
+    type Error = ReplayError;
+
+    fn try_from(raw: RawGameRecord) -> Result<GameRecord, ReplayError> {
+        let mut record = new_record();
+        for (ply, &m) in raw.moves.iter().enumerate() {
+            record.play(m).map_err(|e| ReplayError::Move(ply, e))?;
+        }
+        record.jump_to(raw.ply).ok_or(ReplayError::Ply(raw.ply))?;
+        Ok(record)
+    }
+}
@@ -0,0 +1,112 @@
+use crate::common::*;
+use quarto::{Difficulty, ALL_DIFFICULTIES};
+
+use std::{
+    cmp::min,
+    io,
+};
+use termion::{
+    color,
+    event::Key
+};
+
+static MENU: [(Difficulty, &str); 4] = [
+    (Difficulty::Beginner, "            Beginner           "),
+    (Difficulty::Casual,   "             Casual            "),
+    (Difficulty::Strong,   "             Strong            "),
+    (Difficulty::Expert,   "             Expert            "),
+];
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct State {
+    // index into MENU
+    cursor: usize,
+    // kept when leaving without saving
+    current: Difficulty,
+    saved: Option<Difficulty>,
+}
+
+impl State {
+    /// starts with the cursor on the current difficulty
+    pub fn new(current: Difficulty) -> State {
+        State { cursor: ALL_DIFFICULTIES.iter().position(|&d| d == current).unwrap_or(0), current, saved: None }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum Action {
+    CursorUp,
+    CursorDown,
+    Submit,
+    Back,
+    Idle,
+}
+
+pub struct SettingsApp {}
+
+impl App for SettingsApp {
+    type State = State;
+    type Action = Action;
+    type Output = Difficulty;
+
+    fn initial_state() -> Self::State {
+        State::new(Difficulty::Casual)
+    }
+
+    fn write_state<W: io::Write>(f: &mut W, state: Self::State) {
+        let mut cursor: (u16, u16) = (1, 2);
+
+        write_banner_at(cursor, f);
+        cursor = (3, 8);
+        write_at(cursor, f, "    computer opponent level    ");
+
+        for (i, (_, label)) in MENU.iter().enumerate() {
+            cursor.1 += 1;
+            if state.cursor == i {
+                f.write_fmt(format_args!("{}{}{}{}",
+                    termion::cursor::Goto(cursor.0, cursor.1),
+                    color::Bg(color::AnsiValue::grayscale(12)),
+                    label,
+                    color::Bg(color::Reset)
+                )).unwrap();
+            } else {
+                write_at(cursor, f, label);
+            }
+        }
+
+        cursor.1 += 2;
+        write_at(cursor, f, "- enter to save, q to go back -");
+    }
+
+    fn action_from(key: Option<std::result::Result<termion::event::Key, std::io::Error>>) -> Self::Action {
+        match key {
+            Some(Ok(key)) => match key {
+                Key::Char('q')  => Action::Back,
+                Key::Char('\n') => Action::Submit,
+                Key::Up         => Action::CursorUp,
+                Key::Down       => Action::CursorDown,
+                _               => Action::Idle,
+            },
+            // throws errors away
+            _ => Action::Idle,
+        }
+    }
+
+    fn step(state: Self::State, action: Self::Action) -> Option<Self::State> {
+        match action {
+            Action::Back => Some(State { saved: Some(state.current), ..state }),
+            Action::Idle => Some(state),
+            Action::Submit => Some(State { saved: Some(MENU[state.cursor].0), ..state }),
+            Action::CursorUp => Some(State { cursor: state.cursor.saturating_sub(1), ..state }),
+            Action::CursorDown => Some(State { cursor: min(MENU.len() - 1, state.cursor + 1), ..state }),
+        }
+    }
+
+    fn output_from(state: Self::State) -> Option<Self::Output> {
+        state.saved
+    }
+}
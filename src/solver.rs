@@ -0,0 +1,144 @@
+use crate::{Game, Move, PassGame, PlaceGame, Player, Final};
+use crate::agent::{chosen_pass, chosen_place, Agent};
+use crate::alpha_beta::{ordered_moves, AlphaBeta};
+use crate::board::Idx;
+use crate::piece::Piece;
+use std::collections::HashMap;
+
+/// The exact value of a position for the player to move, assuming perfect play.
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(PartialOrd, Ord)]
+#[derive(Debug)]
+pub enum Value {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl Value {
+    /// the same position's value for the other player
+    pub fn flip(self) -> Value {
+        match self {
+            Value::Loss => Value::Win,
+            Value::Draw => Value::Draw,
+            Value::Win => Value::Loss,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct Solution {
+    /// a move that keeps the value
+    pub best: Move,
+    pub value: Value,
+}
+
+/// Solves any game with at most `max_empty` empty squares. Solved positions are kept
+/// between calls, with every rearrangement of a position sharing one entry.
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct Solver {
+    max_empty: usize,
+    // the lower and upper bounds known for each canonical position, keyed by its
+    // board and the code of the piece in hand
+    table: HashMap<(u16, u64, Option<u8>), (Value, Value)>,
+}
+
+impl Solver {
+    pub fn new(max_empty: usize) -> Solver {
+        Solver { max_empty, table: HashMap::new() }
+    }
+
+    /// None = the game is over or has more than `max_empty` empty squares
+    pub fn solve(&mut self, game: &Game) -> Option<Solution> {
+        if game.is_final() || 16 - game.piece_count() > self.max_empty {
+            return None;
+        }
+        let player = game.player_to_move()?;
+        let mut best = None;
+        for m in ordered_moves(game) {
+            // only a better value than the best so far needs to be exact
+            let alpha = best.map_or(Value::Loss, |(_, v)| v);
+            let v = self.child_value(player, game.apply(m).unwrap(), alpha, Value::Win);
+            if best.is_none_or(|(_, b)| v > b) {
+                best = Some((m, v));
+            }
+            if v == Value::Win {
+                break;
+            }
+        }
+        best.map(|(best, value)| Solution { best, value })
+    }
+
+    // the solved move, or the alpha-beta one while too many squares are empty
+    fn choose(&mut self, game: &Game) -> Option<Move> {
+        match self.solve(game) {
+            Some(solution) => Some(solution.best),
+            None => AlphaBeta::default().search(game).map(|a| a.best),
+        }
+    }
+
+    // the value of an unfinished game for the player to move. a value at or below alpha
+    // only bounds the real value from above, and one at or above beta only from below.
+    fn value(&mut self, game: Game, alpha: Value, beta: Value) -> Value {
+        let (canonical, _) = game.canonicalize();
+        let key = (canonical.board().occupied(), canonical.board().pieces(), canonical.get_passed_piece().map(|p| p.to_bits()));
+        let (lower, upper) = self.table.get(&key).copied().unwrap_or((Value::Loss, Value::Win));
+        if lower == upper || lower >= beta {
+            return lower;
+        }
+        if upper <= alpha {
+            return upper;
+        }
+
+        let (alpha, beta) = (alpha.max(lower), beta.min(upper));
+        let player = game.player_to_move().unwrap();
+        let mut best = Value::Loss;
+        for m in ordered_moves(&game) {
+            let v = self.child_value(player, game.apply(m).unwrap(), alpha.max(best), beta);
+            best = best.max(v);
+            if best >= beta {
+                break;
+            }
+        }
+
+        let bounds = if best <= alpha {
+            (lower, best)
+        } else if best >= beta {
+            (best, upper)
+        } else {
+            (best, best)
+        };
+        self.table.insert(key, bounds);
+        best
+    }
+
+    // the value of the game after `player` moved, for `player`
+    fn child_value(&mut self, player: Player, child: Game, alpha: Value, beta: Value) -> Value {
+        match child {
+            Final(g) => match g.winner() {
+                Some(p) if p == player => Value::Win,
+                Some(_) => Value::Loss,
+                None => Value::Draw,
+            },
+            c if c.player_to_move() == Some(player) => self.value(c, alpha, beta),
+            c => self.value(c, beta.flip(), alpha.flip()).flip(),
+        }
+    }
+}
+
+// plays perfectly once few enough squares are empty and searches with alpha-beta before then
+impl Agent for Solver {
+    fn choose_pass(&mut self, game: &PassGame) -> Piece {
+        let chosen = self.choose(&game.to_game());
+        chosen_pass(game, chosen)
+    }
+
+    fn choose_place(&mut self, game: &PlaceGame) -> (Idx, Idx) {
+        let chosen = self.choose(&game.to_game());
+        chosen_place(game, chosen)
+    }
+}
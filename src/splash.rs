@@ -1,24 +1,20 @@
 use crate::common::*;
 
-use std::io;
+use std::{
+    cmp::min,
+    io,
+};
 use termion::{
     color,
     event::Key
 };
 
-#[derive(Copy, Clone)]
-#[derive(PartialEq, Eq, Hash)]
-#[derive(Debug)]
-pub enum Row {
-    Top,
-    Bottom,
-}
-
 #[derive(Copy, Clone)]
 #[derive(PartialEq, Eq, Hash)]
 #[derive(Debug)]
 pub struct State {
-    cursor: Row,
+    // index into MENU
+    cursor: usize,
     mode: Option<Mode>,
 }
 
@@ -38,9 +34,18 @@ pub enum Action {
 #[derive(Debug)]
 pub enum Mode {
     PassAndPlay,
+    Computer,
     LocalNetwork,
+    Settings,
 }
 
+static MENU: [(Mode, &str); 4] = [
+    (Mode::PassAndPlay,  "         Pass and Play         "),
+    (Mode::Computer,     "       Play the Computer       "),
+    (Mode::LocalNetwork, "         Local Network         "),
+    (Mode::Settings,     "            Settings           "),
+];
+
 pub struct SplashApp {}
 
 impl App for SplashApp {
@@ -49,7 +54,7 @@ impl App for SplashApp {
     type Output = Mode;
 
     fn initial_state() -> Self::State {
-        State { cursor: Row::Top, mode: None }
+        State { cursor: 0, mode: None }
     }
 
     fn write_state<W: io::Write>(f: &mut W, state: Self::State) {
@@ -58,25 +63,18 @@ impl App for SplashApp {
         write_banner_at(cursor, f);
         cursor = (3, 8);
 
-        cursor.1 += 1;
-        if state.cursor == Row::Top {
-            f.write_fmt(format_args!("{}{}         Pass and Play         {}",
-                termion::cursor::Goto(cursor.0, cursor.1),
-                color::Bg(color::AnsiValue::grayscale(12)),
-                color::Bg(color::Reset)
-            )).unwrap();
-
-            cursor.1 += 1;
-            write_at(cursor, f, "         Local Network         ");
-        } else {
-            write_at(cursor, f, "         Pass and Play         ");
-
+        for (i, (_, label)) in MENU.iter().enumerate() {
             cursor.1 += 1;
-            f.write_fmt(format_args!("{}{}         Local Network         {}",
-                termion::cursor::Goto(cursor.0, cursor.1),
-                color::Bg(color::AnsiValue::grayscale(12)),
-                color::Bg(color::Reset)
-            )).unwrap();
+            if state.cursor == i {
+                f.write_fmt(format_args!("{}{}{}{}",
+                    termion::cursor::Goto(cursor.0, cursor.1),
+                    color::Bg(color::AnsiValue::grayscale(12)),
+                    label,
+                    color::Bg(color::Reset)
+                )).unwrap();
+            } else {
+                write_at(cursor, f, label);
+            }
         }
 
         cursor.1 += 2;
@@ -98,13 +96,12 @@ impl App for SplashApp {
     }
     
     fn step(state: Self::State, action: Self::Action) -> Option<Self::State> {
-        match (action, state.cursor) {
-            (Action::Quit, _) => None,
-            (Action::Idle, _) => Some(state),
-            (Action::Submit, Row::Top) => Some(State { cursor: state.cursor, mode: Some(Mode::PassAndPlay) }),
-            (Action::Submit, Row::Bottom) => Some(State { cursor: state.cursor, mode: Some(Mode::LocalNetwork) }),
-            (Action::CursorUp, _) => Some(State { cursor: Row::Top, mode: state.mode }),
-            (Action::CursorDown, _) => Some(State { cursor: Row::Bottom, mode: state.mode }),
+        match action {
+            Action::Quit => None,
+            Action::Idle => Some(state),
+            Action::Submit => Some(State { cursor: state.cursor, mode: Some(MENU[state.cursor].0) }),
+            Action::CursorUp => Some(State { cursor: state.cursor.saturating_sub(1), mode: state.mode }),
+            Action::CursorDown => Some(State { cursor: min(MENU.len() - 1, state.cursor + 1), mode: state.mode }),
         }
     }
 
@@ -0,0 +1,189 @@
+use crate::{FinalGame, Game, Move, PassGame, PlaceGame, Final, Pass, Place};
+use crate::board::{Board, Idx, Square, ALL_SQUARES};
+use crate::piece::Piece;
+use std::sync::OnceLock;
+
+/// A rearrangement of a position that plays exactly like the original. Squares move so
+/// that every line is still a line: the board's rotations and reflections, and swapping
+/// its inner and outer rows and columns. Pieces are relabeled by swapping which
+/// attribute is which and which value of each attribute is which, so pieces that shared
+/// an attribute still share one.
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct Transform {
+    // square i moves to squares[i]
+    squares: [usize; 16],
+    // the piece with code n becomes the piece with code codes[n]
+    codes: [u8; 16],
+}
+
+impl Transform {
+    pub fn identity() -> Transform {
+        let mut t = Transform { squares: [0; 16], codes: [0; 16] };
+        for i in 0..16 {
+            t.squares[i] = i;
+            t.codes[i] = i as u8;
+        }
+        t
+    }
+
+    /// undoes this transform
+    pub fn inverse(&self) -> Transform {
+        let mut t = Transform { squares: [0; 16], codes: [0; 16] };
+        for i in 0..16 {
+            t.squares[self.squares[i]] = i;
+            t.codes[self.codes[i] as usize] = i as u8;
+        }
+        t
+    }
+
+    pub fn map_square(&self, square: (Idx, Idx)) -> (Idx, Idx) {
+        ALL_SQUARES[self.squares[Square::from(square).index()]]
+    }
+
+    pub fn map_piece(&self, p: Piece) -> Piece {
+        Piece::from_bits(self.codes[p.to_bits() as usize])
+    }
+
+    pub fn map_move(&self, m: Move) -> Move {
+        match m {
+            Move::Pass(p) => Move::Pass(self.map_piece(p)),
+            Move::Place(h, v) => {
+                let (h, v) = self.map_square((h, v));
+                Move::Place(h, v)
+            },
+        }
+    }
+
+    pub fn map_board(&self, b: &Board) -> Board {
+        b.rearranged(&self.squares, &self.codes)
+    }
+
+    /// the players stay the same
+    pub fn map_game(&self, g: &Game) -> Game {
+        match *g {
+            Pass(g) => Pass(PassGame { board: self.map_board(&g.board), player: g.player }),
+            Place(g) => Place(PlaceGame { board: self.map_board(&g.board), passed: self.map_piece(g.passed), player: g.player }),
+            Final(g) => Final(FinalGame { board: self.map_board(&g.board), player: g.player }),
+        }
+    }
+}
+
+/// Every transform: each of the 32 ways to move the squares with each of the 384 ways
+/// to relabel the pieces.
+pub fn all_transforms() -> Vec<Transform> {
+    square_maps().iter()
+        .flat_map(|&squares| code_maps().iter().map(move |&codes| Transform { squares, codes }))
+        .collect()
+}
+
+// the board and piece in hand that sort first of everything the transforms make from
+// them. the occupied squares sort first, then the pieces from the last square back,
+// then the piece in hand.
+pub(crate) fn canonical(board: &Board, hand: Option<Piece>) -> (Board, Option<Piece>, Transform) {
+    let occupancies: Vec<u16> = square_maps().iter().map(|s| moved_occupancy(board.occupied(), s)).collect();
+    let least = *occupancies.iter().min().unwrap();
+    let mut best: Option<((u16, u64, u8), Transform)> = None;
+
+    for (squares, _) in square_maps().iter().zip(occupancies).filter(|&(_, o)| o == least) {
+        let mut from = [0; 16];
+        for (i, &j) in squares.iter().enumerate() {
+            from[j] = i;
+        }
+
+        // relabelings that give the least code to each occupied square in turn. the first
+        // square can always get code 0, from one flip for each order of the bits.
+        let mut squares_left = (0..16).rev().filter(|&j| least & 1 << j != 0).map(|j| board.code(from[j]) as usize);
+        let mut codes: Vec<&[u8; 16]> = match squares_left.next() {
+            Some(first) => code_maps().chunks(16).map(|flips| &flips[flips[0][first] as usize]).collect(),
+            None => code_maps().iter().collect(),
+        };
+        for code in squares_left {
+            let min = codes.iter().map(|c| c[code]).min().unwrap();
+            codes.retain(|c| c[code] == min);
+        }
+        let codes = match hand {
+            Some(p) => *codes.iter().min_by_key(|c| c[p.to_bits() as usize]).unwrap(),
+            None => codes[0],
+        };
+
+        let t = Transform { squares: *squares, codes: *codes };
+        let key = (least, t.map_board(board).pieces(), hand.map_or(16, |p| t.codes[p.to_bits() as usize]));
+        if best.is_none_or(|(k, _)| key < k) {
+            best = Some((key, t));
+        }
+    }
+
+    // there is always at least one square map
+    let t = best.unwrap().1;
+    (t.map_board(board), hand.map(|p| t.map_piece(p)), t)
+}
+
+fn moved_occupancy(occupied: u16, squares: &[usize; 16]) -> u16 {
+    (0..16).filter(|&i| occupied & 1 << i != 0).fold(0, |moved, i| moved | 1 << squares[i])
+}
+
+// every ordering of four things
+fn permutations() -> Vec<[usize; 4]> {
+    (0..256)
+        .map(|n| [n & 3, n >> 2 & 3, n >> 4 & 3, n >> 6 & 3])
+        .filter(|order| (0..4).all(|i| order.contains(&i)))
+        .collect()
+}
+
+// reorderings of the rows that keep both diagonals as lines when the columns are reordered
+// the same way. these are the ones that keep each row and its mirror image together.
+fn line_orders() -> Vec<[usize; 4]> {
+    permutations().into_iter()
+        .filter(|order| (0..4).all(|i| order[3 - i] == 3 - order[i]))
+        .collect()
+}
+
+// built once and shared
+static SQUARE_MAPS: OnceLock<Vec<[usize; 16]>> = OnceLock::new();
+static CODE_MAPS: OnceLock<Vec<[u8; 16]>> = OnceLock::new();
+
+fn square_maps() -> &'static [[usize; 16]] {
+    SQUARE_MAPS.get_or_init(build_square_maps)
+}
+
+fn code_maps() -> &'static [[u8; 16]] {
+    CODE_MAPS.get_or_init(build_code_maps)
+}
+
+fn build_square_maps() -> Vec<[usize; 16]> {
+    let mut maps = vec![];
+    for rows in line_orders() {
+        // reversing the columns as well swaps the two diagonals
+        for &reverse in &[false, true] {
+            let cols = if reverse { [3 - rows[0], 3 - rows[1], 3 - rows[2], 3 - rows[3]] } else { rows };
+            for &transpose in &[false, true] {
+                let mut squares = [0; 16];
+                for (i, square) in squares.iter_mut().enumerate() {
+                    let (r, c) = (rows[i / 4], cols[i % 4]);
+                    *square = if transpose { c * 4 + r } else { r * 4 + c };
+                }
+                maps.push(squares);
+            }
+        }
+    }
+    maps
+}
+
+// each code's bits are reordered, then any of them are flipped. the 16 flips of each
+// order come together, starting with no flips.
+fn build_code_maps() -> Vec<[u8; 16]> {
+    let mut maps = vec![];
+    for bits in permutations() {
+        for flip in 0..16 {
+            let mut codes = [0; 16];
+            for (n, code) in codes.iter_mut().enumerate() {
+                let moved = (0..4).filter(|&b| n & 1 << b != 0).fold(0, |c, b| c | 1 << bits[b]);
+                *code = moved as u8 ^ flip;
+            }
+            maps.push(codes);
+        }
+    }
+    maps
+}
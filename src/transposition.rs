@@ -0,0 +1,81 @@
+/// Whether an entry's value is exact or only bounds the real value.
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum Bound {
+    Exact,
+    /// the real value is at least this
+    Lower,
+    /// the real value is at most this
+    Upper,
+}
+
+/// Which entry a full slot keeps.
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub enum Replace {
+    /// the newest entry always wins
+    Always,
+    /// an entry from a shallower search never replaces one from a deeper search
+    /// of a different position
+    DepthPreferred,
+}
+
+#[derive(Copy, Clone)]
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+pub struct Entry<T> {
+    /// the full key, usually from `Game::zobrist`
+    pub key: u64,
+    /// how many plies were searched below the position
+    pub depth: usize,
+    pub bound: Bound,
+    pub value: T,
+}
+
+/// A fixed number of slots that remember search results by key. Each key has one slot,
+/// so different positions can push each other out, but a lookup never returns an entry
+/// for a different key.
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct TranspositionTable<T> {
+    slots: Vec<Option<Entry<T>>>,
+    replace: Replace,
+}
+
+impl<T: Copy> TranspositionTable<T> {
+    /// always has at least one slot
+    pub fn new(slots: usize, replace: Replace) -> TranspositionTable<T> {
+        TranspositionTable { slots: vec![None; slots.max(1)], replace }
+    }
+
+    /// None = nothing is stored for this key
+    pub fn get(&self, key: u64) -> Option<Entry<T>> {
+        self.slots[self.slot(key)].filter(|e| e.key == key)
+    }
+
+    /// may be ignored, depending on the replacement policy
+    pub fn insert(&mut self, entry: Entry<T>) {
+        let slot = self.slot(entry.key);
+        let keep_old = match (self.replace, self.slots[slot]) {
+            (Replace::DepthPreferred, Some(old)) => old.key != entry.key && old.depth > entry.depth,
+            _ => false,
+        };
+        if !keep_old {
+            self.slots[slot] = Some(entry);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|s| *s = None);
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        (key % self.slots.len() as u64) as usize
+    }
+}
@@ -0,0 +1,53 @@
+// Zobrist keys: a random number for each piece on each square, for each piece in hand,
+// and for P2 to move. A position's key is the XOR of the numbers for everything in it,
+// so a move updates the key by XORing in only what changed. The numbers come from a
+// fixed seed so keys are the same from run to run.
+
+/// the key for the piece with code `code` on square i is at `PIECE_KEYS[i][code]`
+pub(crate) static PIECE_KEYS: [[u64; 16]; 16] = piece_keys();
+pub(crate) static HAND_KEYS: [u64; 16] = hand_keys();
+pub(crate) static P2_KEY: u64 = nth(272);
+
+// one step of the splitmix64 generator: the next state and its output
+const fn splitmix(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+// the nth output of the generator
+const fn nth(n: u64) -> u64 {
+    let mut state = 0;
+    let mut i = 0;
+    while i < n {
+        state = splitmix(state).0;
+        i += 1;
+    }
+    splitmix(state).1
+}
+
+const fn piece_keys() -> [[u64; 16]; 16] {
+    let mut keys = [[0; 16]; 16];
+    let mut i = 0;
+    while i < 16 {
+        let mut code = 0;
+        while code < 16 {
+            keys[i][code] = nth((i * 16 + code) as u64);
+            code += 1;
+        }
+        i += 1;
+    }
+    keys
+}
+
+const fn hand_keys() -> [u64; 16] {
+    let mut keys = [0; 16];
+    let mut code = 0;
+    while code < 16 {
+        keys[code] = nth((256 + code) as u64);
+        code += 1;
+    }
+    keys
+}
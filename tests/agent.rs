@@ -0,0 +1,175 @@
+mod common;
+use common::random_record;
+use quarto::*;
+use quickcheck_macros::quickcheck;
+
+#[quickcheck]
+fn random_agents_finish_every_game(seed1: u64, seed2: u64) -> bool {
+    let mut p1 = RandomAgent::new(seed1);
+    let mut p2 = RandomAgent::new(seed2);
+    play_out(Pass(quarto::new_game()), &mut p1, &mut p2).is_ok()
+}
+
+#[quickcheck]
+fn random_agents_are_deterministic(seed: u64) -> bool {
+    let play = || play_out(Pass(quarto::new_game()), &mut RandomAgent::new(seed), &mut RandomAgent::new(seed.wrapping_add(1)));
+    play() == play()
+}
+
+#[quickcheck]
+fn random_agents_only_choose_legal_moves(seed: u64) -> bool {
+    let mut agent = RandomAgent::new(seed);
+    let mut game = Pass(quarto::new_game());
+    let mut legal = true;
+    while let Some(m) = agent.choose_move(&game) {
+        legal = legal && game.legal_moves().contains(&m);
+        game = game.apply(m).unwrap();
+    }
+    legal && game.is_final()
+}
+
+#[test]
+fn play_out_leaves_final_games_alone() {
+    let mut p1 = RandomAgent::new(0);
+    let mut p2 = RandomAgent::new(1);
+    let done = play_out(Pass(quarto::new_game()), &mut p1, &mut p2).unwrap();
+    assert_eq!(play_out(done.to_game(), &mut p1, &mut p2), Ok(done));
+}
+
+// three tall white pieces in the top row, with WTQH still unplaced
+fn one_from_a_win() -> Game {
+    "WTRFWTRHWTQF1/1BSQH2/2BSRF1/4 pass - P2".parse().unwrap()
+}
+
+#[test]
+fn alpha_beta_places_a_winning_piece() {
+    let game = one_from_a_win().apply(Move::Pass(WTQH)).unwrap();
+    let analysis = AlphaBeta::default().search(&game).unwrap();
+    assert_eq!(analysis.best, Move::Place(I1, I4));
+    assert_eq!(analysis.score, Score::Win(1));
+}
+
+#[test]
+fn alpha_beta_never_passes_a_winning_piece() {
+    let analysis = AlphaBeta { depth: 1, node_limit: None }.search(&one_from_a_win()).unwrap();
+    let next = one_from_a_win().apply(analysis.best).unwrap();
+    assert!(next.legal_moves().into_iter().all(|m| !next.apply(m).unwrap().has_win()));
+}
+
+#[test]
+fn alpha_beta_has_no_move_once_the_game_is_over() {
+    let game = one_from_a_win()
+        .apply(Move::Pass(WTQH)).unwrap()
+        .apply(Move::Place(I1, I4)).unwrap();
+    assert_eq!(AlphaBeta::default().search(&game), None);
+}
+
+#[quickcheck]
+fn alpha_beta_plays_legal_moves_within_any_node_limit(seed: u64, limit: u8) -> bool {
+    let mut engine = AlphaBeta { depth: 32, node_limit: Some(u64::from(limit)) };
+    play_out(Pass(quarto::new_game()), &mut RandomAgent::new(seed), &mut engine).is_ok()
+}
+
+#[test]
+fn alpha_beta_beats_random_play() {
+    let mut engine = AlphaBeta { depth: 3, node_limit: None };
+    let wins = (0..10)
+        .filter(|&seed| {
+            let game = play_out(Pass(quarto::new_game()), &mut RandomAgent::new(seed), &mut engine).unwrap();
+            game.winner() == Some(P2)
+        })
+        .count();
+    assert!(wins >= 8, "won {} of 10", wins);
+}
+
+#[test]
+fn mcts_reports_every_legal_move() {
+    let game = Pass(quarto::new_game());
+    let candidates = Mcts { iterations: 500, time_limit: None, seed: 1 }.search(&game);
+    let legal = game.legal_moves();
+    assert_eq!(candidates.len(), legal.len());
+    assert!(legal.iter().all(|m| candidates.iter().any(|c| c.action == *m)));
+    assert_eq!(candidates.iter().map(|c| c.visits).sum::<u64>(), 500);
+    assert!(candidates.windows(2).all(|w| w[0].visits >= w[1].visits));
+    assert!(candidates.iter().all(|c| c.win_rate() >= 0.0 && c.win_rate() <= 1.0));
+}
+
+#[test]
+fn mcts_is_deterministic_for_a_seed() {
+    let mcts = Mcts { iterations: 300, time_limit: None, seed: 9 };
+    let game = one_from_a_win();
+    assert_eq!(mcts.search(&game), mcts.search(&game));
+}
+
+#[test]
+fn mcts_places_a_winning_piece() {
+    let game = one_from_a_win().apply(Move::Pass(WTQH)).unwrap();
+    let best = Mcts { iterations: 2000, time_limit: None, seed: 0 }.search(&game)[0];
+    assert_eq!(best.action, Move::Place(I1, I4));
+    assert_eq!(best.win_rate(), 1.0);
+}
+
+#[test]
+fn mcts_beats_random_play() {
+    let mut engine = Mcts { iterations: 200, time_limit: None, seed: 0 };
+    let wins = (0..10)
+        .filter(|&seed| {
+            let game = play_out(Pass(quarto::new_game()), &mut RandomAgent::new(seed), &mut engine).unwrap();
+            game.winner() == Some(P2)
+        })
+        .count();
+    assert!(wins >= 7, "won {} of 10", wins);
+}
+
+// a few random moves, so that levels that never blunder still play different games
+fn opening(seed: u64) -> Game {
+    random_record(seed, 4).game()
+}
+
+// games won by `higher` and by `lower` when they play each opening from both seats
+fn wins_and_losses(higher: Difficulty, lower: Difficulty, openings: u64) -> (usize, usize) {
+    let (mut wins, mut losses) = (0, 0);
+    for seed in 0..openings {
+        for &higher_seat in &[P1, P2] {
+            let mut h = higher.agent(seed);
+            let mut l = lower.agent(seed);
+            let game = match higher_seat {
+                P1 => play_out(opening(seed), &mut h, &mut l),
+                P2 => play_out(opening(seed), &mut l, &mut h),
+            };
+            match game.unwrap().winner() {
+                Some(p) if p == higher_seat => wins += 1,
+                Some(_) => losses += 1,
+                None => {},
+            }
+        }
+    }
+    (wins, losses)
+}
+
+#[test]
+fn difficulties_parse_from_their_names() {
+    for &d in &ALL_DIFFICULTIES {
+        assert_eq!(d.to_string().parse(), Ok(d));
+        assert_eq!(d.to_string().to_uppercase().parse(), Ok(d));
+    }
+    assert!("impossible".parse::<Difficulty>().is_err());
+}
+
+// expert starts with eight squares left, where it solves instead of searching, so a hundred
+// games stay quick. its search plays whole games in the self-play test below.
+#[quickcheck]
+fn every_difficulty_plays_legal_moves(seed: u64) -> bool {
+    ALL_DIFFICULTIES.iter().all(|&d| {
+        let start = if d == Difficulty::Expert { random_record(seed, 16).game() } else { Pass(quarto::new_game()) };
+        play_out(start, &mut RandomAgent::new(seed), &mut d.agent(seed)).is_ok()
+    })
+}
+
+#[test]
+fn each_difficulty_beats_the_one_below() {
+    for pair in ALL_DIFFICULTIES.windows(2) {
+        let (wins, losses) = wins_and_losses(pair[1], pair[0], 6);
+        assert!(wins > losses, "{} won {} and lost {} against {}", pair[1], wins, losses, pair[0]);
+    }
+}
@@ -0,0 +1,44 @@
+mod common;
+use common::random_record;
+use quarto::*;
+use quickcheck_macros::quickcheck;
+
+#[quickcheck]
+fn winning_squares_are_the_places_that_win(seed: u64, plies: u8) -> bool {
+    // an odd number of plies always ends on a place, unless the game is over
+    match random_record(seed, plies as usize % 16 * 2 + 1).game() {
+        Place(g) => {
+            let wins: Vec<(Idx, Idx)> = g.legal_places()
+                .filter(|&sq| g.place(sq).unwrap().either(|f| f.winner().is_some(), |_| false))
+                .collect();
+            g.winning_squares() == wins
+        },
+        _ => true,
+    }
+}
+
+#[quickcheck]
+fn unsafe_pieces_give_away_a_win(seed: u64, plies: u8) -> bool {
+    match random_record(seed, plies as usize % 16 * 2).game() {
+        Pass(g) => {
+            let unsafe_pieces: Vec<Piece> = g.legal_passes()
+                .filter(|&p| !g.pass(p).unwrap().winning_squares().is_empty())
+                .collect();
+            g.unsafe_pieces() == unsafe_pieces
+                && g.is_forced_loss() == (unsafe_pieces.len() == g.legal_passes().count())
+        },
+        _ => true,
+    }
+}
+
+#[test]
+fn a_pass_with_only_unsafe_pieces_is_a_forced_loss() {
+    let game: Game = "2WSQF1/BSQH1WSRH1/WTRHBSRFWTQHWTQF/BTRHBTQF1BSRH place BSQF P2".parse().unwrap();
+    match game.apply(Move::Place(I1, I1)).unwrap() {
+        Pass(g) => {
+            assert!(g.is_forced_loss());
+            assert_eq!(g.unsafe_pieces().len(), g.legal_passes().count());
+        },
+        g => panic!("expected a pass, found {}", g),
+    }
+}
@@ -0,0 +1,16 @@
+// helpers shared by the test files and the benchmark
+use quarto::*;
+
+// a game of seeded random moves, stopped after `plies` moves or when the game ends
+pub fn random_record(seed: u64, plies: usize) -> GameRecord {
+    let mut agent = RandomAgent::new(seed);
+    let mut record = quarto::new_record();
+    while record.ply() < plies {
+        match agent.choose_move(&record.game()) {
+            None => break,
+            // the agent only chooses legal moves
+            Some(m) => { record.play(m).unwrap(); },
+        }
+    }
+    record
+}
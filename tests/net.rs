@@ -0,0 +1,74 @@
+use quarto::*;
+use std::thread;
+
+// passes the pieces in order and places each on the next square
+fn script(pieces: &[Piece]) -> Vec<Move> {
+    pieces.iter().zip(ALL_SQUARES.iter())
+        .flat_map(|(p, square)| vec![Move::Pass(*p), Move::Place(square.0, square.1)])
+        .collect()
+}
+
+// fills the board without ever making a line that shares an attribute
+static TIE: [Piece; 16] = [
+    BSRF, BTRF, WTQF, WSQH,
+    WSRH, WTRF, BTQF, WSRF,
+    BTQH, BSQF, BTRH, WTQH,
+    WTRH, BSRH, WSQF, BSQH,
+];
+
+// the host plays as P1
+fn play_side(mut conn: Connection, me: Player, script: Vec<Move>) -> Game {
+    let mut game = Pass(quarto::new_game());
+    for m in script {
+        if game.is_final() {
+            break;
+        }
+        if game.player_to_move() == Some(me) {
+            conn.send(m).unwrap();
+        } else {
+            assert_eq!(conn.recv().unwrap(), m);
+        }
+        game = game.apply(m).unwrap();
+    }
+    game
+}
+
+// plays the script from both ends of a connection
+fn play_over_network(script: Vec<Move>) -> (Game, Game) {
+    let host = Host::bind("127.0.0.1:0").unwrap();
+    let addr = host.local_addr().unwrap();
+
+    let join_script = script.clone();
+    let joiner = thread::spawn(move || play_side(Connection::join(addr).unwrap(), P2, join_script));
+    let host_game = play_side(host.accept().unwrap(), P1, script);
+    (host_game, joiner.join().unwrap())
+}
+
+#[test]
+fn host_and_join_play_to_a_win() {
+    let (host_game, join_game) = play_over_network(script(&ALL_PIECES));
+    assert!(host_game.is_final());
+    assert!(host_game.has_win());
+    assert_eq!(host_game, join_game);
+}
+
+#[test]
+fn host_and_join_play_a_full_game_to_a_tie() {
+    let (host_game, join_game) = play_over_network(script(&TIE));
+    assert!(host_game.is_tie());
+    assert_eq!(host_game.piece_count(), 16);
+    assert_eq!(host_game, join_game);
+}
+
+#[test]
+fn try_recv_does_not_block() {
+    let host = Host::bind("127.0.0.1:0").unwrap();
+    let addr = host.local_addr().unwrap();
+
+    let mut joined = Connection::join(addr).unwrap();
+    let mut hosted = host.accept().unwrap();
+
+    assert_eq!(joined.try_recv().unwrap(), None);
+    hosted.send(Move::Pass(WTRF)).unwrap();
+    assert_eq!(joined.recv().unwrap(), Move::Pass(WTRF));
+}
@@ -3,31 +3,21 @@ use rand::seq::SliceRandom;
 use rand::thread_rng;
 use itertools::interleave;
 use arrayvec::ArrayVec;
-use either::Either;
-use quickcheck;
 use quickcheck_macros::*;
 use quickcheck::{Arbitrary, Gen};
-use Turn::*;
 
 
-#[derive(Copy, Clone)]
-#[derive(Debug)]
-enum Turn {
-    PassTurn(Piece),
-    PlaceTurn((Idx, Idx)),
-}
-
 #[derive(Copy, Clone)]
 #[derive(Debug)]
 struct Run {
-    turns: [Turn; 32],
+    turns: [Move; 32],
 }
 
 impl Run {
     fn play(&self) -> Option<Game> {
-        self.turns.iter().fold(
-            Some(Pass(quarto::new_game())), 
-            |game, &turn| game.and_then(|g| play(g, turn))
+        self.turns.iter().try_fold(
+            Pass(quarto::new_game()),
+            |game, &turn| play(game, turn)
         )
     }
 }
@@ -43,30 +33,59 @@ impl Arbitrary for Run {
 
         let interleaved: ArrayVec<[_; 32]> = 
             interleave(
-                pieces.iter().map(|x| PassTurn(*x)),
-                squares.iter().map(|x| PlaceTurn(*x)))
-            .into_iter()
+                pieces.iter().map(|x| Move::Pass(*x)),
+                squares.iter().map(|x| Move::Place(x.0, x.1)))
             .collect();
         
         Run { turns: interleaved.into_inner().unwrap() }
     }
 }
 
-fn play(game: Game, turn: Turn) -> Option<Game> {
+// a run where the sixteenth piece is placed without an earlier win
+#[derive(Copy, Clone)]
+#[derive(Debug)]
+struct LastSquareRun {
+    run: Run,
+}
+
+impl LastSquareRun {
+    fn arbitrary_where(g: &mut Gen, last_place_wins: bool) -> LastSquareRun {
+        loop {
+            let run = Run::arbitrary(g);
+            if let Some(Final(f)) = run.play() {
+                if f.to_game().piece_count() == 16 && f.to_game().has_win() == last_place_wins {
+                    return LastSquareRun { run };
+                }
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+#[derive(Debug)]
+struct LastSquareWin(LastSquareRun);
+
+#[derive(Copy, Clone)]
+#[derive(Debug)]
+struct LastSquareDraw(LastSquareRun);
+
+impl Arbitrary for LastSquareWin {
+    fn arbitrary(g: &mut Gen) -> LastSquareWin {
+        LastSquareWin(LastSquareRun::arbitrary_where(g, true))
+    }
+}
+
+impl Arbitrary for LastSquareDraw {
+    fn arbitrary(g: &mut Gen) -> LastSquareDraw {
+        LastSquareDraw(LastSquareRun::arbitrary_where(g, false))
+    }
+}
+
+// finished games ignore any remaining turns
+fn play(game: Game, turn: Move) -> Option<Game> {
     match game {
         g @ Game::Final(_) => Some(g),
-        Game::Pass(g) => match turn {
-            PassTurn(p) => g.pass(p).map(|x| Place(x)),
-            _ => None,
-        },
-        Game::Place(g) => match turn {
-            PlaceTurn(p) => match g.place(p) {
-                Some(Either::Left(next_g)) => Some(Final(next_g)),
-                Some(Either::Right(next_g)) => Some(Pass(next_g)),
-                _ => None,
-            },
-            _ => None,
-        },
+        g => g.apply(turn).ok(),
     }
 }
 
@@ -77,10 +96,7 @@ fn new_game_is_empty() {
 
 #[quickcheck]
 fn all_games_end(r: Run) -> bool {
-    match r.play() {
-        Some(Final(_)) => true,
-        _ => false,
-    }
+    matches!(r.play(), Some(Final(_)))
 }
 
 #[quickcheck]
@@ -103,4 +119,497 @@ fn detects_ties_as_final(r: Run) -> bool {
         // no generated run should fail
         _ => false,
     }
-}
\ No newline at end of file
+}
+
+#[quickcheck]
+fn wins_are_reported_for_every_winning_line(r: Run) -> bool {
+    match r.play() {
+        Some(Final(g)) => {
+            let wins = g.wins();
+            let lines_share_attributes = wins.iter().all(|w| {
+                let pieces: Vec<Piece> = w.line.squares().iter()
+                    .map(|&(h, v)| g.to_game().board().get(h, v).unwrap())
+                    .collect();
+                !w.attributes.is_empty() && w.attributes.iter().all(|a| pieces.iter().all(|p| match a {
+                    Attribute::C(c) => p.color == *c,
+                    Attribute::H(h) => p.height == *h,
+                    Attribute::S(s) => p.shape == *s,
+                    Attribute::T(t) => p.top == *t,
+                }))
+            });
+            lines_share_attributes && (wins.is_empty() != g.to_game().has_win())
+        },
+        _ => false,
+    }
+}
+
+#[test]
+fn reports_the_winning_row_and_attributes() {
+    let turns = [
+        Move::Pass(WTRF), Move::Place(I1, I1),
+        Move::Pass(BSQH), Move::Place(I2, I1),
+        Move::Pass(WTRH), Move::Place(I1, I2),
+        Move::Pass(BSQF), Move::Place(I2, I2),
+        Move::Pass(WTQF), Move::Place(I1, I3),
+        Move::Pass(BSRH), Move::Place(I3, I1),
+        Move::Pass(WTQH), Move::Place(I1, I4),
+    ];
+    let game = turns.iter().try_fold(Pass(quarto::new_game()), |g, &t| play(g, t));
+
+    match game {
+        Some(Final(g)) => assert_eq!(g.wins(), vec![Win {
+            line: Line::Row(I1),
+            attributes: vec![Attribute::C(Color::White), Attribute::H(Height::Tall)],
+        }]),
+        other => panic!("expected a final game, got {:?}", other),
+    }
+}
+
+#[quickcheck]
+fn winner_placed_the_last_piece(r: Run) -> bool {
+    match r.play() {
+        // P2 makes the first placement, so P2 places every odd numbered piece
+        Some(Final(g)) => match g.outcome() {
+            Outcome::Win(p) => {
+                let last_placer = if g.to_game().piece_count() % 2 == 1 { P2 } else { P1 };
+                g.winner() == Some(p) && p == last_placer
+            },
+            Outcome::Draw => g.winner().is_none() && !g.to_game().has_win(),
+        },
+        _ => false,
+    }
+}
+
+#[quickcheck]
+fn players_alternate_pass_and_place(r: Run) -> bool {
+    let mut game = Pass(quarto::new_game());
+    let mut players = vec![];
+    for &turn in r.turns.iter() {
+        match game.player_to_move() {
+            None => break,
+            Some(p) => players.push(p),
+        }
+        game = play(game, turn).unwrap();
+    }
+    // P1 passes, P2 places, P2 passes, P1 places, ...
+    players.iter().enumerate().all(|(i, &p)| p == if matches!(i % 4, 0 | 3) { P1 } else { P2 })
+}
+
+#[quickcheck]
+fn win_on_the_last_square_is_not_a_tie(r: LastSquareWin) -> bool {
+    match r.0.run.play() {
+        // P2 places every odd numbered piece, so P1 places the sixteenth
+        Some(g@Final(f)) => !g.is_tie() && f.outcome() == Outcome::Win(P1) && g.outcome() == Some(Outcome::Win(P1)),
+        _ => false,
+    }
+}
+
+#[quickcheck]
+fn full_board_without_a_win_is_a_draw(r: LastSquareDraw) -> bool {
+    match r.0.run.play() {
+        Some(g@Final(f)) => g.is_tie() && f.outcome() == Outcome::Draw && f.wins().is_empty(),
+        _ => false,
+    }
+}
+
+impl Run {
+    // the moves of a run up to and including the one that ends the game
+    fn moves(&self) -> Vec<Move> {
+        let mut game = Pass(quarto::new_game());
+        let mut moves = vec![];
+        for &turn in self.turns.iter() {
+            if game.is_final() {
+                break;
+            }
+            game = play(game, turn).unwrap();
+            moves.push(turn);
+        }
+        moves
+    }
+}
+
+#[quickcheck]
+fn record_undo_and_redo_round_trip(r: Run) -> bool {
+    let mut record = quarto::new_record();
+    let played = r.moves().iter().all(|&m| record.play(m).is_ok());
+    let final_game = record.game();
+
+    let undone = (0..record.moves().len()).all(|_| record.undo().is_some());
+    let at_start = record.game() == Pass(quarto::new_game()) && record.undo().is_none();
+    let redone = (0..record.moves().len()).all(|_| record.redo().is_some());
+
+    played && undone && at_start && redone && record.redo().is_none() && record.game() == final_game
+}
+
+#[quickcheck]
+fn record_jumps_to_any_ply(r: Run, ply: usize) -> bool {
+    let moves = r.moves();
+    let ply = ply % (moves.len() + 1);
+    let mut record = quarto::new_record();
+    moves.iter().for_each(|&m| { record.play(m).unwrap(); });
+
+    let expected = r.turns[..ply].iter().try_fold(Pass(quarto::new_game()), |g, &t| play(g, t));
+    record.jump_to(ply) == expected && record.ply() == ply && record.history() == &moves[..ply]
+}
+
+#[quickcheck]
+fn record_play_after_undo_discards_redo(r: Run) -> bool {
+    let moves = r.moves();
+    let mut record = quarto::new_record();
+    moves.iter().for_each(|&m| { record.play(m).unwrap(); });
+
+    record.undo();
+    record.undo();
+    let replayed = record.play(moves[moves.len() - 2]).is_ok();
+    replayed && record.moves().len() == moves.len() - 1 && record.redo().is_none()
+}
+
+#[test]
+fn illegal_moves_report_why() {
+    let mut record = quarto::new_record();
+    assert_eq!(record.play(Move::Place(I1, I1)), Err(MoveError::ExpectedPass));
+    record.play(Move::Pass(WTRF)).unwrap();
+    assert_eq!(record.play(Move::Pass(WTRF)), Err(MoveError::PieceInHand));
+    assert_eq!(record.play(Move::Pass(BSQH)), Err(MoveError::ExpectedPlace));
+    record.play(Move::Place(I1, I1)).unwrap();
+    assert_eq!(record.play(Move::Pass(WTRF)), Err(MoveError::PieceOnBoard));
+    record.play(Move::Pass(WTRH)).unwrap();
+    assert_eq!(record.play(Move::Place(I1, I1)), Err(MoveError::SquareOccupied));
+}
+
+#[quickcheck]
+fn finished_games_reject_every_move(r: Run) -> bool {
+    let mut record = quarto::new_record();
+    r.moves().iter().for_each(|&m| { record.play(m).unwrap(); });
+    record.play(Move::Pass(WTRF)) == Err(MoveError::GameOver)
+        && record.play(Move::Place(I1, I1)) == Err(MoveError::GameOver)
+}
+
+#[quickcheck]
+fn legal_moves_are_exactly_the_moves_that_succeed(r: Run, plies: usize) -> bool {
+    let moves = r.moves();
+    let mut record = quarto::new_record();
+    moves[..plies % (moves.len() + 1)].iter().for_each(|&m| { record.play(m).unwrap(); });
+    let game = record.game();
+    let legal = game.legal_moves();
+
+    let mut candidates = ALL_PIECES.iter().map(|&p| Move::Pass(p))
+        .chain(ALL_SQUARES.iter().map(|&(h, v)| Move::Place(h, v)));
+    candidates.all(|m| {
+        record.clone().play(m).is_ok() == legal.contains(&m)
+    })
+}
+
+#[quickcheck]
+fn legal_move_counts_match_the_board(r: Run, plies: usize) -> bool {
+    let moves = r.moves();
+    let mut record = quarto::new_record();
+    moves[..plies % (moves.len() + 1)].iter().for_each(|&m| { record.play(m).unwrap(); });
+    let empty_squares = 16 - record.game().piece_count();
+
+    match record.game() {
+        Pass(g) => g.legal_passes().count() == empty_squares,
+        Place(g) => g.legal_places().count() == empty_squares,
+        Final(_) => record.game().legal_moves().is_empty(),
+    }
+}
+
+#[quickcheck]
+fn board_holds_every_placed_piece(r: Run) -> bool {
+    let moves = r.moves();
+    let game = moves.iter().try_fold(Pass(quarto::new_game()), |g, &m| play(g, m)).unwrap();
+    let placed: Vec<(Piece, (Idx, Idx))> = moves.chunks(2)
+        .filter_map(|pair| match pair {
+            [Move::Pass(p), Move::Place(h, v)] => Some((*p, (*h, *v))),
+            _ => None,
+        })
+        .collect();
+
+    let raw = game.board().raw();
+    placed.len() == game.piece_count()
+        && placed.iter().all(|&(p, (h, v))| game.board().get(h, v) == Some(p) && game.board().contains(&p))
+        && placed.iter().all(|&(p, (h, v))| raw[h.to_i()][v.to_i()] == Some(p))
+}
+
+#[test]
+fn piece_codes_are_indices_into_all_pieces() {
+    for (i, p) in ALL_PIECES.iter().enumerate() {
+        assert_eq!(p.to_bits() as usize, i);
+        assert_eq!(Piece::from_bits(i as u8), *p);
+    }
+}
+
+#[quickcheck]
+fn piece_codes_round_trip(bits: u8) -> bool {
+    use std::convert::TryFrom;
+    match Piece::try_from(bits) {
+        Ok(p) => bits < 16 && u8::from(p) == bits
+            && Color::from_bits(bits) == p.color
+            && Height::from_bits(bits) == p.height
+            && Shape::from_bits(bits) == p.shape
+            && Top::from_bits(bits) == p.top,
+        Err(e) => bits >= 16 && e == InvalidPieceCode(bits),
+    }
+}
+
+#[test]
+fn pieces_parse_from_their_notation() {
+    for p in ALL_PIECES.iter() {
+        assert_eq!(p.to_string().parse::<Piece>(), Ok(*p));
+        assert_eq!(p.to_string().to_lowercase().parse::<Piece>(), Ok(*p));
+    }
+    assert_eq!("wTqH".parse::<Piece>(), Ok(WTQH));
+}
+
+#[test]
+fn bad_piece_notation_says_what_is_wrong() {
+    assert_eq!("WTR".parse::<Piece>(), Err(ParsePieceError::Length(3)));
+    assert_eq!("WTRFH".parse::<Piece>(), Err(ParsePieceError::Length(5)));
+    assert_eq!("XTRF".parse::<Piece>(), Err(ParsePieceError::Letter { index: 0, found: 'X' }));
+    assert_eq!("WtxF".parse::<Piece>(), Err(ParsePieceError::Letter { index: 2, found: 'x' }));
+    assert_eq!(
+        "BSQz".parse::<Piece>().unwrap_err().to_string(),
+        "expected top F or H at position 4, found 'z'"
+    );
+}
+
+#[test]
+fn squares_round_trip_through_notation_and_index() {
+    for (i, &sq) in ALL_SQUARES.iter().enumerate() {
+        let square = Square::from(sq);
+        assert_eq!(square.to_string().parse::<Square>(), Ok(square));
+        assert_eq!(square.to_string().to_uppercase().parse::<Square>(), Ok(square));
+        assert_eq!(Square::from_index(i), Some(square));
+        assert_eq!(square.index(), i);
+        assert_eq!((square.row(), square.col()), sq);
+        assert_eq!(Idx::from_i(sq.0.to_i()), Some(sq.0));
+    }
+    assert_eq!(Square::new(I1, I1).to_string(), "a1");
+    assert_eq!(Square::new(I1, I4).to_string(), "d1");
+    assert_eq!(Square::new(I4, I1).to_string(), "a4");
+}
+
+#[test]
+fn bad_squares_say_what_is_wrong() {
+    use std::convert::TryFrom;
+    assert_eq!(Square::try_from(16), Err(InvalidIndex(16)));
+    assert_eq!(Idx::try_from(4), Err(InvalidIndex(4)));
+    assert_eq!("a".parse::<Square>(), Err(ParseSquareError::Length(1)));
+    assert_eq!("e1".parse::<Square>(), Err(ParseSquareError::Column('e')));
+    assert_eq!("a5".parse::<Square>(), Err(ParseSquareError::Row('5')));
+}
+
+#[quickcheck]
+fn position_notation_round_trips(r: Run) -> bool {
+    let mut game = Pass(quarto::new_game());
+    let mut round_trips = game.to_string().parse::<Game>() == Ok(game);
+    for m in r.moves() {
+        game = game.apply(m).unwrap();
+        round_trips = round_trips && game.to_string().parse::<Game>() == Ok(game);
+    }
+    round_trips
+}
+
+#[test]
+fn position_notation_examples() {
+    let new_game = Pass(quarto::new_game());
+    let passed = new_game.apply(Move::Pass(WTRF)).unwrap();
+    let placed = passed.apply(Move::Place(I1, I1)).unwrap();
+
+    assert_eq!(new_game.to_string(), "4/4/4/4 pass - P1");
+    assert_eq!(passed.to_string(), "4/4/4/4 place WTRF P2");
+    assert_eq!(placed.to_string(), "WTRF3/4/4/4 pass - P2");
+    assert_eq!("2bsqh1/4/4/wtrf3 Place WTQF p2".parse::<Game>().map(|g| g.to_string()),
+        Ok(String::from("2BSQH1/4/4/WTRF3 place WTQF P2")));
+}
+
+#[test]
+fn bad_position_notation_says_what_is_wrong() {
+    assert_eq!("4/4/4/4 pass -".parse::<Game>(), Err(ParseGameError::Fields(3)));
+    assert_eq!("4/4/4 pass - P1".parse::<Game>(), Err(ParseGameError::Rows(3)));
+    assert_eq!("4/WTRF4/4/4 pass - P1".parse::<Game>(), Err(ParseGameError::Row(1)));
+    assert_eq!("4/4/4/WTR pass - P1".parse::<Game>(), Err(ParseGameError::Row(3)));
+    assert_eq!("4/22/4/4 pass - P1".parse::<Game>(), Err(ParseGameError::Row(1)));
+    assert_eq!("WTRF12/4/4/4 pass - P2".parse::<Game>(), Err(ParseGameError::Row(0)));
+    assert_eq!("XTRF3/4/4/4 pass - P1".parse::<Game>(),
+        Err(ParseGameError::Piece(ParsePieceError::Letter { index: 0, found: 'X' })));
+    assert_eq!("4/4/4/4 wait - P1".parse::<Game>(), Err(ParseGameError::Phase));
+    assert_eq!("4/4/4/4 pass - P3".parse::<Game>(), Err(ParseGameError::Player));
+    assert_eq!("WTRFWTRF2/4/4/4 pass - P1".parse::<Game>(),
+        Err(ParseGameError::Position(PositionError::DuplicatePiece(WTRF))));
+    assert_eq!("WTRF3/4/4/4 place WTRF P1".parse::<Game>(),
+        Err(ParseGameError::Position(PositionError::PieceInHandOnBoard(WTRF))));
+    assert_eq!("4/4/4/4 place - P1".parse::<Game>(),
+        Err(ParseGameError::Position(PositionError::MissingPieceInHand)));
+    assert_eq!("WTRFWTRHWTQFWTQH/4/4/4 pass - P1".parse::<Game>(),
+        Err(ParseGameError::Position(PositionError::AlreadyWon)));
+    assert_eq!("WTRF3/4/4/4 final - P2".parse::<Game>(),
+        Err(ParseGameError::Position(PositionError::NotOver)));
+    assert_eq!("WTRF3/4/4/4 pass - P1".parse::<Game>(),
+        Err(ParseGameError::Position(PositionError::WrongPlayer(P1))));
+    assert_eq!("4/4/4/4 place WTRF P1".parse::<Game>(),
+        Err(ParseGameError::Position(PositionError::WrongPlayer(P1))));
+}
+
+#[quickcheck]
+fn game_files_round_trip(r: Run, player1: String, other: Vec<(String, String)>) -> bool {
+    let mut record = quarto::new_record();
+    r.moves().iter().for_each(|&m| { record.play(m).unwrap(); });
+    let mut tags = Tags::default();
+    for (name, value) in other {
+        // names that could not be read back are turned away
+        let _ = tags.push(&name, &value);
+    }
+    let header = Header {
+        player1,
+        other: tags,
+        ..Header::default()
+    };
+    let file = GameFile { header, record };
+
+    file.to_string().parse::<GameFile>() == Ok(file)
+}
+
+#[test]
+fn game_file_example() {
+    let text = "\
+[Player1 \"Ann\"]
+[Player2 \"Bo \\\"the bot\\\"\"]
+[Date \"2021.01.30\"]
+[Variant \"standard\"]
+[Result \"0-1\"]
+[Event \"club night\"]
+
+1. WTRF a1 2. BSQH a2 3. WTRH b1 4. BSQF b2 5. WTQF c1 6. BSRH a3 7. WTQH d1 0-1
+";
+    let file = text.parse::<GameFile>().unwrap();
+    assert_eq!(file.header.player2, "Bo \"the bot\"");
+    assert_eq!(file.header.other.iter().collect::<Vec<_>>(), vec![("Event", "club night")]);
+    assert_eq!(file.record.ply(), 14);
+    assert_eq!(file.record.game().outcome(), Some(Outcome::Win(P2)));
+    assert_eq!(file.to_string(), text);
+}
+
+#[test]
+fn tag_values_with_control_characters_are_escaped() {
+    let mut header = Header { player1: String::from("Ann\nBo"), ..Header::default() };
+    header.other.push("Event", "a\r\tb\u{7}").unwrap();
+    let file = GameFile { header, record: quarto::new_record() };
+    let text = file.to_string();
+    assert!(text.contains("[Player1 \"Ann\\nBo\"]"));
+    assert!(text.contains("[Event \"a\\r\\tb\\u{7}\"]"));
+    assert_eq!(text.parse::<GameFile>(), Ok(file));
+}
+
+#[test]
+fn bad_game_files_say_what_is_wrong() {
+    assert_eq!("[Player1 Ann]\n\n*".parse::<GameFile>(), Err(ReadGameError::Tag(1)));
+    assert_eq!("[Event \"x\"]\n[Round-2 \"3\"]\n\n*".parse::<GameFile>(), Err(ReadGameError::Tag(2)));
+    assert_eq!("1. WTRF e1 *".parse::<GameFile>(), Err(ReadGameError::Square(ParseSquareError::Column('e'))));
+    assert_eq!("1. WTRX a1 *".parse::<GameFile>(),
+        Err(ReadGameError::Piece(ParsePieceError::Letter { index: 3, found: 'X' })));
+    assert_eq!("1. WTRF a1 2. WTRF b1 *".parse::<GameFile>(), Err(ReadGameError::Move(2, MoveError::PieceOnBoard)));
+    assert_eq!("1. WTRF a1 1-0".parse::<GameFile>(), Err(ReadGameError::Result));
+    assert_eq!("[Result \"1-0\"]\n\n1. WTRF a1".parse::<GameFile>(), Err(ReadGameError::Result));
+    assert_eq!("1. WTRF a1 * 2. BSQH b1".parse::<GameFile>(), Err(ReadGameError::AfterResult));
+}
+
+#[test]
+fn tags_that_would_not_read_back_are_refused() {
+    let mut tags = Tags::default();
+    for &name in &["Result", "Player1", "Two Words", "Round]", "", "é"] {
+        assert_eq!(tags.push(name, "1-0"), Err(InvalidTagName(String::from(name))));
+    }
+    assert_eq!(tags.push("Round_2", "3"), Ok(()));
+    assert_eq!(tags.get("Round_2"), Some("3"));
+    assert_eq!(tags.len(), 1);
+}
+
+#[quickcheck]
+fn every_played_position_can_be_rebuilt(r: Run) -> bool {
+    let mut game = Pass(quarto::new_game());
+    let mut rebuilt = true;
+    for m in r.moves() {
+        game = game.apply(m).unwrap();
+        let player = match game {
+            Final(g) => g.last_player(),
+            g => g.player_to_move().unwrap(),
+        };
+        let board = Board::from_squares(game.board().raw()).unwrap();
+        rebuilt = rebuilt && Game::from_position(board, game.get_passed_piece(), player) == Ok(game);
+    }
+    rebuilt
+}
+
+#[test]
+fn positions_are_built_without_replaying() {
+    let squares = [
+        [Some(WTRF), Some(WTRH), Some(WTQF), None],
+        [None, Some(BSQH), None, None],
+        [None, None, Some(BSRF), None],
+        [None, None, None, None],
+    ];
+    let board = Board::from_squares(squares).unwrap();
+    assert_eq!(board.raw(), squares);
+
+    let pass = Game::from_position(board, None, P2).unwrap();
+    assert_eq!(pass.player_to_move(), Some(P2));
+    assert_eq!(pass.to_string(), "WTRFWTRHWTQF1/1BSQH2/2BSRF1/4 pass - P2");
+
+    let place = Game::from_position(board, Some(WTQH), P1).unwrap();
+    assert_eq!(place.apply(Move::Place(I1, I4)).unwrap().outcome(), Some(Outcome::Win(P1)));
+}
+
+#[test]
+fn impossible_positions_are_rejected() {
+    let mut squares = [[None; 4]; 4];
+    squares[0][0] = Some(WTRF);
+    squares[3][3] = Some(WTRF);
+    assert_eq!(Board::from_squares(squares), Err(PositionError::DuplicatePiece(WTRF)));
+
+    let won = Board::from_squares([
+        [Some(WTRF), Some(WTRH), Some(WTQF), Some(WTQH)],
+        [None, None, None, None],
+        [None, None, None, None],
+        [None, None, None, None],
+    ]).unwrap();
+    assert_eq!(Game::from_position(won, Some(BSQH), P1), Err(PositionError::UnexpectedPieceInHand));
+    assert_eq!(Game::from_position(won, Some(WTRF), P1), Err(PositionError::PieceInHandOnBoard(WTRF)));
+    assert!(Game::from_position(won, None, P1).unwrap().is_final());
+
+    // two wins in rows that share no square could not both be made by the last piece
+    let two_wins = Board::from_squares([
+        [Some(WTRF), Some(WTRH), Some(WTQF), Some(WTQH)],
+        [None, None, None, None],
+        [None, None, None, None],
+        [Some(BSRF), Some(BSRH), Some(BSQF), Some(BSQH)],
+    ]).unwrap();
+    assert_eq!(Game::from_position(two_wins, None, P1), Err(PositionError::SeparateWins));
+}
+
+#[test]
+fn the_player_must_fit_the_number_of_pieces() {
+    let empty = quarto::new_board();
+    let mut squares = [[None; 4]; 4];
+    squares[0][0] = Some(WTRF);
+    let one = Board::from_squares(squares).unwrap();
+    let won = Board::from_squares([
+        [Some(WTRF), Some(WTRH), Some(WTQF), Some(WTQH)],
+        [None, None, None, None],
+        [None, None, None, None],
+        [None, None, None, None],
+    ]).unwrap();
+
+    // P1 passes first, then P2 places and passes
+    assert_eq!(Game::from_position(empty, None, P2), Err(PositionError::WrongPlayer(P2)));
+    assert_eq!(Game::from_position(one, None, P1), Err(PositionError::WrongPlayer(P1)));
+    assert!(Game::from_position(one, None, P2).is_ok());
+
+    assert_eq!(Game::from_position(empty, Some(WTRF), P1), Err(PositionError::WrongPlayer(P1)));
+    assert_eq!(Game::from_position(one, Some(BSQH), P2), Err(PositionError::WrongPlayer(P2)));
+    assert!(Game::from_position(one, Some(BSQH), P1).is_ok());
+
+    // the fourth piece was placed by P1
+    assert_eq!(Game::from_position(won, None, P2), Err(PositionError::WrongPlayer(P2)));
+    assert_eq!(Game::from_position(won, None, P1).map(|g| g.outcome()), Ok(Some(Outcome::Win(P1))));
+}
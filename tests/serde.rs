@@ -0,0 +1,89 @@
+#![cfg(feature = "serde")]
+// run with `cargo test --features serde`
+mod common;
+use common::random_record;
+use quarto::*;
+
+#[test]
+fn games_round_trip_through_json() {
+    for seed in 0..100 {
+        let mut record = random_record(seed, 32);
+        while let Some(game) = record.undo() {
+            let json = serde_json::to_string(&game).unwrap();
+            assert_eq!(serde_json::from_str::<Game>(&json).unwrap(), game);
+        }
+    }
+}
+
+#[test]
+fn records_round_trip_through_json() {
+    let mut record = random_record(0, 32);
+    record.undo();
+    let json = serde_json::to_string(&record).unwrap();
+    assert_eq!(serde_json::from_str::<GameRecord>(&json).unwrap(), record);
+}
+
+#[test]
+fn pieces_and_moves_round_trip_through_json() {
+    for &p in ALL_PIECES.iter() {
+        let m = Move::Pass(p);
+        assert_eq!(serde_json::from_str::<Move>(&serde_json::to_string(&m).unwrap()).unwrap(), m);
+    }
+    for &(h, v) in ALL_SQUARES.iter() {
+        let sq = Square::new(h, v);
+        assert_eq!(serde_json::from_str::<Square>(&serde_json::to_string(&sq).unwrap()).unwrap(), sq);
+    }
+}
+
+#[test]
+fn illegal_boards_are_rejected() {
+    let wtrf = serde_json::to_string(&WTRF).unwrap();
+    let duplicate = format!("[[{p},{p},null,null],[null,null,null,null],[null,null,null,null],[null,null,null,null]]", p = wtrf);
+    assert!(serde_json::from_str::<Board>(&duplicate).is_err());
+
+    let row: Vec<String> = [WTRF, WTRH, WTQF, WTQH].iter().map(|p| serde_json::to_string(p).unwrap()).collect();
+    let winning_board = format!("[[{}],[null,null,null,null],[null,null,null,null],[null,null,null,null]]", row.join(","));
+    let won_pass = format!("{{\"Pass\":{{\"board\":{},\"player\":\"P1\"}}}}", winning_board);
+    assert!(serde_json::from_str::<Game>(&won_pass).is_err());
+
+    let won_final = format!("{{\"Final\":{{\"board\":{},\"player\":\"P1\"}}}}", winning_board);
+    assert!(serde_json::from_str::<Game>(&won_final).unwrap().is_final());
+}
+
+#[test]
+fn passed_piece_must_be_off_the_board() {
+    let placed = Pass(quarto::new_game()).apply(Move::Pass(WTRF)).unwrap().apply(Move::Place(I1, I1)).unwrap();
+    let json = serde_json::to_string(&placed).unwrap();
+    // P1 places the piece P2 passes next
+    let passing = |p: Piece| json
+        .replacen("{\"Pass\":{", &format!("{{\"Place\":{{\"passed\":{},", serde_json::to_string(&p).unwrap()), 1)
+        .replacen("\"P2\"", "\"P1\"", 1);
+    assert!(serde_json::from_str::<Game>(&passing(WTRF)).is_err());
+    assert_eq!(serde_json::from_str::<Game>(&passing(BSQH)).unwrap().get_passed_piece(), Some(BSQH));
+}
+
+#[test]
+fn the_player_must_fit_the_board() {
+    let placed = Pass(quarto::new_game()).apply(Move::Pass(WTRF)).unwrap().apply(Move::Place(I1, I1)).unwrap();
+    let json = serde_json::to_string(&placed).unwrap();
+    assert_eq!(serde_json::from_str::<Game>(&json).unwrap(), placed);
+    // P2 placed the only piece, so P2 passes next
+    assert!(serde_json::from_str::<Game>(&json.replace("\"P2\"", "\"P1\"")).is_err());
+}
+
+#[test]
+fn records_that_cannot_be_replayed_are_rejected() {
+    let mut record = quarto::new_record();
+    record.play(Move::Pass(WTRF)).unwrap();
+    let json = serde_json::to_string(&record).unwrap();
+    assert_eq!(serde_json::from_str::<GameRecord>(&json).unwrap(), record);
+
+    let past_the_end = json.replace("\"ply\":1", "\"ply\":5");
+    let e = serde_json::from_str::<GameRecord>(&past_the_end).unwrap_err();
+    assert!(e.to_string().starts_with(&ReplayError::Ply(5).to_string()), "{}", e);
+
+    let pass = serde_json::to_string(&Move::Pass(WTRF)).unwrap();
+    let passed_twice = json.replace("\"ply\":1", "\"ply\":2").replacen(']', &format!(",{}]", pass), 1);
+    let e = serde_json::from_str::<GameRecord>(&passed_twice).unwrap_err();
+    assert!(e.to_string().starts_with(&ReplayError::Move(1, MoveError::PieceInHand).to_string()), "{}", e);
+}
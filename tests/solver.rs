@@ -0,0 +1,87 @@
+mod common;
+use common::random_record;
+use quarto::*;
+use quickcheck_macros::quickcheck;
+
+// plays random moves until at most `empty` squares are left. None = the game ended first.
+fn endgame(seed: u64, empty: usize) -> Option<Game> {
+    // each place fills a square, and each pass comes before one
+    let game = random_record(seed, 2 * (16 - empty)).game();
+    Some(game).filter(|g| !g.is_final())
+}
+
+// plain minimax over every move, for the player to move
+fn minimax(game: Game) -> Value {
+    let player = game.player_to_move().unwrap();
+    game.legal_moves().into_iter()
+        .map(|m| match game.apply(m).unwrap() {
+            Final(g) if g.winner() == Some(player) => Value::Win,
+            Final(g) if g.winner().is_some() => Value::Loss,
+            Final(_) => Value::Draw,
+            g if g.player_to_move() == Some(player) => minimax(g),
+            g => minimax(g).flip(),
+        })
+        .max()
+        .unwrap()
+}
+
+#[quickcheck]
+fn solver_agrees_with_minimax(seed: u64) -> bool {
+    match endgame(seed, 4) {
+        None => true,
+        Some(game) => Solver::new(4).solve(&game).map(|s| s.value) == Some(minimax(game)),
+    }
+}
+
+#[quickcheck]
+fn solver_best_moves_keep_the_value(seed: u64) -> bool {
+    let game = match endgame(seed, 6) {
+        None => return true,
+        Some(g) => g,
+    };
+    let mut solver = Solver::new(6);
+    let solution = solver.solve(&game).unwrap();
+    let player = game.player_to_move();
+    match game.apply(solution.best).unwrap() {
+        Final(g) => match g.winner() {
+            Some(_) => solution.value == Value::Win,
+            None => solution.value == Value::Draw,
+        },
+        next => {
+            let value = solver.solve(&next).unwrap().value;
+            let same_player = next.player_to_move() == player;
+            solution.value == if same_player { value } else { value.flip() }
+        },
+    }
+}
+
+#[quickcheck]
+fn solver_only_solves_within_its_limit(seed: u64) -> bool {
+    match endgame(seed, 9) {
+        None => true,
+        Some(game) => Solver::new(8).solve(&game).is_none(),
+    }
+}
+
+#[test]
+fn solver_takes_an_immediate_win() {
+    let game: Game = "2WSQF1/BSQH1WSRH1/WTRHBSRFWTQHWTQF/BTRHBTQF1BSRH place BSQF P2".parse().unwrap();
+    let solution = Solver::new(16).solve(&game).unwrap();
+    assert_eq!(solution, Solution { best: Move::Place(I4, I3), value: Value::Win });
+    assert_eq!(Solver::new(16).solve(&game.apply(solution.best).unwrap()), None);
+}
+
+#[quickcheck]
+fn alpha_beta_agrees_with_the_solver_when_it_searches_to_the_end(seed: u64) -> bool {
+    let game = match endgame(seed, 6) {
+        None => return true,
+        Some(g) => g,
+    };
+    let value = Solver::new(6).solve(&game).unwrap().value;
+    let score = AlphaBeta { depth: 12, node_limit: None }.search(&game).unwrap().score;
+    match score {
+        Score::Win(_) => value == Value::Win,
+        Score::Loss(_) => value == Value::Loss,
+        Score::Draw => value == Value::Draw,
+    }
+}
@@ -0,0 +1,53 @@
+mod common;
+use common::random_record;
+use quarto::*;
+use quickcheck_macros::quickcheck;
+use std::collections::HashSet;
+
+fn transform(n: usize) -> Transform {
+    let all = all_transforms();
+    all[n % all.len()]
+}
+
+#[test]
+fn there_are_12288_distinct_transforms() {
+    let all: HashSet<Transform> = all_transforms().into_iter().collect();
+    assert_eq!(all.len(), 32 * 24 * 16);
+    assert!(all.contains(&Transform::identity()));
+}
+
+#[quickcheck]
+fn transforms_play_the_same(seed: u64, moves: u8, n: usize) -> bool {
+    let t = transform(n);
+    let record = random_record(seed, moves as usize % 33);
+    let game = record.game();
+    let mut mapped = Pass(quarto::new_game());
+    for &m in record.moves() {
+        mapped = mapped.apply(t.map_move(m)).unwrap();
+    }
+    mapped == t.map_game(&game) && mapped.outcome() == game.outcome()
+}
+
+#[quickcheck]
+fn inverse_transforms_undo(seed: u64, moves: u8, n: usize) -> bool {
+    let t = transform(n);
+    let game = random_record(seed, moves as usize % 33).game();
+    t.inverse().map_game(&t.map_game(&game)) == game
+        && t.map_game(&t.inverse().map_game(&game)) == game
+}
+
+#[quickcheck]
+fn rearranged_games_share_a_canonical_form(seed: u64, moves: u8, n: usize) -> bool {
+    let game = random_record(seed, moves as usize % 33).game();
+    let (canonical, t) = game.canonicalize();
+    let (other, _) = transform(n).map_game(&game).canonicalize();
+    canonical == other && t.map_game(&game) == canonical
+}
+
+#[test]
+fn boards_canonicalize_like_games() {
+    let game = random_record(3, 12).game();
+    let (board, t) = game.board().canonicalize();
+    assert_eq!(t.map_board(game.board()), board);
+    assert_eq!(*game.canonicalize().0.board(), board);
+}
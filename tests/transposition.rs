@@ -0,0 +1,43 @@
+mod common;
+use common::random_record;
+use quarto::*;
+use quickcheck_macros::quickcheck;
+
+#[quickcheck]
+fn zobrist_keys_do_not_depend_on_move_order(seed: u64, plies: u8) -> bool {
+    let game = random_record(seed, plies as usize % 33).game();
+    // parsing places the pieces row by row instead of in the order they were played
+    let parsed: Game = game.to_string().parse().unwrap();
+    parsed.zobrist() == game.zobrist()
+}
+
+#[quickcheck]
+fn zobrist_keys_differ_between_positions_of_a_game(seed: u64, a: u8, b: u8) -> bool {
+    let mut record = random_record(seed, 32);
+    let (a, b) = (a as usize % (record.ply() + 1), b as usize % (record.ply() + 1));
+    let key_a = record.jump_to(a).unwrap().zobrist();
+    let key_b = record.jump_to(b).unwrap().zobrist();
+    (a == b) == (key_a == key_b)
+}
+
+#[test]
+fn transposition_tables_keep_deeper_entries_when_asked() {
+    let entry = |key, depth| Entry { key, depth, bound: Bound::Exact, value: depth };
+
+    let mut table = TranspositionTable::new(4, Replace::DepthPreferred);
+    table.insert(entry(1, 5));
+    table.insert(entry(5, 2));
+    assert_eq!(table.get(1), Some(entry(1, 5)));
+    assert_eq!(table.get(5), None);
+    table.insert(entry(1, 1));
+    assert_eq!(table.get(1), Some(entry(1, 1)));
+
+    let mut table = TranspositionTable::new(4, Replace::Always);
+    table.insert(entry(1, 5));
+    table.insert(entry(5, 2));
+    assert_eq!(table.get(1), None);
+    assert_eq!(table.get(5), Some(entry(5, 2)));
+    table.clear();
+    assert_eq!(table.get(5), None);
+    assert_eq!(table.capacity(), 4);
+}